@@ -0,0 +1,106 @@
+use std::env::args;
+
+use rand::prelude::*;
+use sedgewick::{
+    doubling::{doubling_test, DoublingReport},
+    graph::{connected_components::ConnectedComponents, undirected_graph::Graph},
+    misc::{three_sum, three_sum_binary, three_sum_fast, two_sum_fast, two_sum_hash},
+    sort::merge_sort::merge_sort,
+    union_find::{erdos_renyi_pairs, union_find, UnionFind, WeightedQuickUnion},
+};
+
+type Counter = fn(&mut [i32]) -> usize;
+
+fn print_report(name: &str, report: &DoublingReport) {
+    println!("{}", name);
+
+    report.rounds.iter().for_each(|round| {
+        println!(
+            "{:>10} {:>12.6} {:>8}",
+            round.size,
+            round.duration.as_secs_f64(),
+            round
+                .ratio
+                .map_or("-".to_string(), |ratio| format!("{:.2}", ratio))
+        );
+    });
+
+    let next = report.rounds.last().map_or(0, |round| 2 * round.size);
+    match (report.fit, report.predict(next)) {
+        (Some((a, b)), Some(predicted)) => println!(
+            "T(N) ~ {:.3e} * N^{:.2}, predicted for {}: {:.3}s\n",
+            a,
+            b,
+            next,
+            predicted.as_secs_f64()
+        ),
+        _ => println!("no power law fit\n"),
+    }
+}
+
+fn main() {
+    let mut args = args().skip(1);
+    let start: usize = args.next().map_or(250, |s| s.parse().unwrap());
+    let rounds: usize = args.next().map_or(5, |s| s.parse().unwrap());
+
+    let counters: [(&str, Counter); 5] = [
+        ("three sum", |list| three_sum(list)),
+        ("three sum binary", three_sum_binary),
+        ("three sum fast", three_sum_fast),
+        ("two sum fast", two_sum_fast),
+        ("two sum hash", |list| two_sum_hash(list)),
+    ];
+
+    let mut rng = thread_rng();
+
+    counters.iter().for_each(|(name, count)| {
+        let report = doubling_test(
+            start,
+            rounds,
+            |n| {
+                (0..n)
+                    .map(|_| rng.gen_range(-1_000_000..1_000_000))
+                    .collect::<Vec<i32>>()
+            },
+            |list| {
+                count(list);
+            },
+        );
+
+        print_report(name, &report);
+    });
+
+    // the same driver with other inputs: floats to sort, a union-find with
+    // its pairs, and a graph
+    let report = doubling_test(
+        start,
+        rounds,
+        |n| (0..n).map(|_| rng.gen::<f64>()).collect::<Vec<f64>>(),
+        |list| merge_sort(list),
+    );
+    print_report("merge sort", &report);
+
+    let report = doubling_test(
+        start,
+        rounds,
+        |n| (WeightedQuickUnion::new(n), erdos_renyi_pairs(n)),
+        |(uf, pairs)| {
+            union_find(uf, pairs);
+        },
+    );
+    print_report("weighted quick union", &report);
+
+    let report = doubling_test(
+        start,
+        rounds,
+        |n| {
+            let mut g = Graph::new(n);
+            (0..2 * n).for_each(|_| g.add_edge(rng.gen_range(0..n), rng.gen_range(0..n)));
+            g
+        },
+        |g| {
+            ConnectedComponents::new(g);
+        },
+    );
+    print_report("connected components", &report);
+}
@@ -3,7 +3,7 @@ use std::{
     time::SystemTime,
 };
 
-use sedgewick::misc::three_sum_binary;
+use sedgewick::misc::{three_sum_binary, three_sum_fast};
 
 fn main() {
     let mut stdin = io::stdin().lock();
@@ -20,4 +20,11 @@ fn main() {
     println!("three sum binary: {}", three_sum_binary(&mut integers));
     let d2 = t2.elapsed().unwrap().as_secs_f64();
     println!("time: {}", d2);
+
+    let t3 = SystemTime::now();
+    println!("three sum fast: {}", three_sum_fast(&mut integers));
+    let d3 = t3.elapsed().unwrap().as_secs_f64();
+    println!("time: {}", d3);
+
+    println!("ratio: {}", d2 / d3);
 }
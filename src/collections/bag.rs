@@ -0,0 +1,184 @@
+use std::fmt::Display;
+
+use crate::singly_linked_list::{self, List};
+
+use super::stack::write_items;
+
+/// Collection that only supports adding and iterating; order is unspecified
+/// but the linked version yields the most recently added item first.
+pub struct Bag<T> {
+    list: List<T>,
+}
+
+impl<T> Bag<T> {
+    pub fn new() -> Self {
+        Bag { list: List::new() }
+    }
+
+    pub fn add(&mut self, item: T) {
+        self.list.push_front(item);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.list.len()
+    }
+
+    pub fn iter(&self) -> singly_linked_list::Iter<'_, T> {
+        self.list.iter()
+    }
+}
+
+impl<T> Default for Bag<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Extend<T> for Bag<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|item| self.add(item));
+    }
+}
+
+impl<T> FromIterator<T> for Bag<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut bag = Bag::new();
+        bag.extend(iter);
+        bag
+    }
+}
+
+impl<T> IntoIterator for Bag<T> {
+    type Item = T;
+
+    type IntoIter = singly_linked_list::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.list.into_iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a Bag<T> {
+    type Item = &'a T;
+
+    type IntoIter = singly_linked_list::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<T: Display> Display for Bag<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write_items(f, self.iter())
+    }
+}
+
+/// Bag on an array that doubles when full; iterates in insertion order.
+pub struct ResizingArrayBag<T> {
+    items: Vec<T>,
+}
+
+impl<T> ResizingArrayBag<T> {
+    pub fn new() -> Self {
+        ResizingArrayBag { items: vec![] }
+    }
+
+    pub fn add(&mut self, item: T) {
+        if self.items.len() == self.items.capacity() {
+            self.items.reserve_exact(self.items.capacity().max(1));
+        }
+
+        self.items.push(item);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
+        self.items.iter()
+    }
+}
+
+impl<T> Default for ResizingArrayBag<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Extend<T> for ResizingArrayBag<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|item| self.add(item));
+    }
+}
+
+impl<T> FromIterator<T> for ResizingArrayBag<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut bag = ResizingArrayBag::new();
+        bag.extend(iter);
+        bag
+    }
+}
+
+impl<T> IntoIterator for ResizingArrayBag<T> {
+    type Item = T;
+
+    type IntoIter = std::vec::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.items.into_iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a ResizingArrayBag<T> {
+    type Item = &'a T;
+
+    type IntoIter = std::slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<T: Display> Display for ResizingArrayBag<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write_items(f, self.iter())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bag() {
+        let mut bag: Bag<f64> = [100.0, 99.0, 101.0].into_iter().collect();
+        bag.add(120.0);
+
+        assert_eq!(bag.len(), 4);
+        assert_eq!(bag.to_string(), "120 101 99 100");
+
+        let mean = bag.iter().sum::<f64>() / bag.len() as f64;
+        assert_eq!(mean, 105.0);
+    }
+
+    #[test]
+    fn resizing_array_bag() {
+        let mut bag = ResizingArrayBag::new();
+        assert!(bag.is_empty());
+
+        bag.extend(1..=5);
+        assert_eq!(bag.len(), 5);
+        assert_eq!(bag.to_string(), "1 2 3 4 5");
+        assert_eq!((&bag).into_iter().sum::<i32>(), 15);
+    }
+}
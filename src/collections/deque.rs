@@ -0,0 +1,563 @@
+use std::{
+    alloc::{alloc, dealloc, handle_alloc_error, Layout},
+    fmt::Debug,
+    marker::PhantomData,
+    mem,
+    ptr::{self, NonNull},
+};
+
+/// Doubly linked list with O(1) push and pop at both ends.
+pub struct Deque<T> {
+    front: Link<T>,
+    back: Link<T>,
+    len: usize,
+    _owns: PhantomData<Box<Node<T>>>,
+}
+
+/// NonNull keeps `Deque<T>` covariant in T, as `Box` would be.
+type Link<T> = Option<NonNull<Node<T>>>;
+
+struct Node<T> {
+    elem: T,
+    prev: Link<T>,
+    next: Link<T>,
+}
+
+impl<T> Node<T> {
+    fn alloc(elem: T, prev: Link<T>, next: Link<T>) -> NonNull<Node<T>> {
+        unsafe {
+            let layout = Layout::new::<Node<T>>();
+            let Some(node) = NonNull::new(alloc(layout) as *mut Node<T>) else {
+                handle_alloc_error(layout);
+            };
+
+            ptr::write(node.as_ptr(), Node { elem, prev, next });
+
+            node
+        }
+    }
+
+    /// Safety: `node` came from `Node::alloc` and is no longer reachable
+    unsafe fn free(node: NonNull<Node<T>>) -> T {
+        let layout = Layout::new::<Node<T>>();
+        let Node { elem, .. } = ptr::read(node.as_ptr());
+
+        dealloc(node.as_ptr() as *mut u8, layout);
+
+        elem
+    }
+}
+
+// The deque owns its nodes like a Box would, so it is as thread-safe as T.
+unsafe impl<T: Send> Send for Deque<T> {}
+unsafe impl<T: Sync> Sync for Deque<T> {}
+
+impl<T> Deque<T> {
+    pub fn new() -> Self {
+        Deque {
+            front: None,
+            back: None,
+            len: 0,
+            _owns: PhantomData,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    // null <- (X) -> null
+    // push_front Y
+    // null <- (Y) <-> (X) -> null
+    pub fn push_front(&mut self, elem: T) {
+        let node = Some(Node::alloc(elem, None, self.front));
+
+        match self.front {
+            None => self.back = node,
+            Some(front) => unsafe { (*front.as_ptr()).prev = node },
+        }
+
+        self.front = node;
+        self.len += 1;
+    }
+
+    pub fn push_back(&mut self, elem: T) {
+        let node = Some(Node::alloc(elem, self.back, None));
+
+        match self.back {
+            None => self.front = node,
+            Some(back) => unsafe { (*back.as_ptr()).next = node },
+        }
+
+        self.back = node;
+        self.len += 1;
+    }
+
+    pub fn pop_front(&mut self) -> Option<T> {
+        let node = self.front?;
+
+        unsafe {
+            self.front = (*node.as_ptr()).next;
+
+            match self.front {
+                None => self.back = None,
+                Some(front) => (*front.as_ptr()).prev = None,
+            }
+
+            self.len -= 1;
+
+            Some(Node::free(node))
+        }
+    }
+
+    /// O(1) thanks to the prev pointer, unlike `List::pop_back`
+    pub fn pop_back(&mut self) -> Option<T> {
+        let node = self.back?;
+
+        unsafe {
+            self.back = (*node.as_ptr()).prev;
+
+            match self.back {
+                None => self.front = None,
+                Some(back) => (*back.as_ptr()).next = None,
+            }
+
+            self.len -= 1;
+
+            Some(Node::free(node))
+        }
+    }
+
+    pub fn front(&self) -> Option<&T> {
+        unsafe { self.front.map(|node| &(*node.as_ptr()).elem) }
+    }
+
+    pub fn front_mut(&mut self) -> Option<&mut T> {
+        unsafe { self.front.map(|node| &mut (*node.as_ptr()).elem) }
+    }
+
+    pub fn back(&self) -> Option<&T> {
+        unsafe { self.back.map(|node| &(*node.as_ptr()).elem) }
+    }
+
+    pub fn back_mut(&mut self) -> Option<&mut T> {
+        unsafe { self.back.map(|node| &mut (*node.as_ptr()).elem) }
+    }
+
+    pub fn clear(&mut self) {
+        while self.pop_front().is_some() {}
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            front: self.front,
+            back: self.back,
+            len: self.len,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut {
+            front: self.front,
+            back: self.back,
+            len: self.len,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T> Default for Deque<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+unsafe impl<T: Sync> Send for Iter<'_, T> {}
+unsafe impl<T: Sync> Sync for Iter<'_, T> {}
+
+unsafe impl<T: Send> Send for IterMut<'_, T> {}
+unsafe impl<T: Sync> Sync for IterMut<'_, T> {}
+
+impl<T> Drop for Deque<T> {
+    fn drop(&mut self) {
+        // If dropping an element panics, keep dropping the rest while
+        // unwinding instead of leaking them. `pop_front` has already unlinked
+        // and freed the node by the time the element is dropped.
+        struct DropGuard<'a, T>(&'a mut Deque<T>);
+
+        impl<T> Drop for DropGuard<'_, T> {
+            fn drop(&mut self) {
+                while self.0.pop_front().is_some() {}
+            }
+        }
+
+        while let Some(elem) = self.pop_front() {
+            let guard = DropGuard(self);
+            drop(elem);
+            mem::forget(guard);
+        }
+    }
+}
+
+impl<T: Clone> Clone for Deque<T> {
+    fn clone(&self) -> Self {
+        self.iter().cloned().collect()
+    }
+}
+
+impl<T: Debug> Debug for Deque<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T: PartialEq> PartialEq for Deque<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Eq> Eq for Deque<T> {}
+
+impl<T> Extend<T> for Deque<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|elem| self.push_back(elem));
+    }
+}
+
+impl<T> FromIterator<T> for Deque<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut deque = Deque::new();
+        deque.extend(iter);
+        deque
+    }
+}
+
+impl<T> IntoIterator for Deque<T> {
+    type Item = T;
+
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter(self)
+    }
+}
+
+pub struct IntoIter<T>(Deque<T>);
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.pop_front()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.0.len, Some(self.0.len))
+    }
+}
+
+impl<T> DoubleEndedIterator for IntoIter<T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.0.pop_back()
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
+/// `len` counts the nodes between `front` and `back`, so the two ends never
+/// cross when iterating from both sides.
+pub struct Iter<'a, T> {
+    front: Link<T>,
+    back: Link<T>,
+    len: usize,
+    _marker: PhantomData<&'a T>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+
+        unsafe {
+            let node = &*self.front.unwrap().as_ptr();
+            self.front = node.next;
+            self.len -= 1;
+
+            Some(&node.elem)
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<T> DoubleEndedIterator for Iter<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+
+        unsafe {
+            let node = &*self.back.unwrap().as_ptr();
+            self.back = node.prev;
+            self.len -= 1;
+
+            Some(&node.elem)
+        }
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+impl<'a, T> IntoIterator for &'a Deque<T> {
+    type Item = &'a T;
+
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+pub struct IterMut<'a, T> {
+    front: Link<T>,
+    back: Link<T>,
+    len: usize,
+    _marker: PhantomData<&'a mut T>,
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+
+        unsafe {
+            let node = &mut *self.front.unwrap().as_ptr();
+            self.front = node.next;
+            self.len -= 1;
+
+            Some(&mut node.elem)
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<T> DoubleEndedIterator for IterMut<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+
+        unsafe {
+            let node = &mut *self.back.unwrap().as_ptr();
+            self.back = node.prev;
+            self.len -= 1;
+
+            Some(&mut node.elem)
+        }
+    }
+}
+
+impl<T> ExactSizeIterator for IterMut<'_, T> {}
+
+impl<'a, T> IntoIterator for &'a mut Deque<T> {
+    type Item = &'a mut T;
+
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        cell::Cell,
+        panic::{catch_unwind, AssertUnwindSafe},
+    };
+
+    use super::{Deque, Iter, IterMut};
+
+    /// compiles only if `Deque<&'static str>` can stand in for `Deque<&'a str>`
+    #[allow(dead_code)]
+    fn covariant<'a>(deque: Deque<&'static str>) -> Deque<&'a str> {
+        deque
+    }
+
+    #[test]
+    fn basics() {
+        let mut deque = Deque::new();
+        assert_eq!(deque.pop_front(), None);
+        assert_eq!(deque.pop_back(), None);
+
+        deque.push_front(2);
+        deque.push_front(1);
+        deque.push_back(3);
+        deque.push_back(4);
+        assert_eq!(deque.len(), 4);
+        assert_eq!(deque.front(), Some(&1));
+        assert_eq!(deque.back(), Some(&4));
+
+        assert_eq!(deque.pop_back(), Some(4));
+        assert_eq!(deque.pop_front(), Some(1));
+        assert_eq!(deque.pop_back(), Some(3));
+        assert_eq!(deque.pop_back(), Some(2));
+        assert_eq!(deque.pop_back(), None);
+        assert!(deque.is_empty());
+
+        // both ends were reset when it emptied
+        deque.push_back(5);
+        assert_eq!(deque.front(), Some(&5));
+        assert_eq!(deque.pop_front(), Some(5));
+        assert_eq!(deque.front(), None);
+        assert_eq!(deque.back(), None);
+    }
+
+    #[test]
+    fn double_ended_iter() {
+        let mut deque: Deque<i32> = (1..=5).collect();
+
+        let mut iter = deque.iter();
+        assert_eq!(iter.len(), 5);
+        assert_eq!(iter.next(), Some(&1));
+        assert_eq!(iter.next_back(), Some(&5));
+        assert_eq!(iter.next(), Some(&2));
+        assert_eq!(iter.next_back(), Some(&4));
+        assert_eq!(iter.next(), Some(&3));
+        assert_eq!(iter.next_back(), None);
+        assert_eq!(iter.next(), None);
+
+        deque.iter_mut().rev().for_each(|x| *x *= 10);
+        if let Some(x) = deque.iter_mut().next_back() {
+            *x += 1;
+        }
+        assert_eq!(
+            deque.iter().copied().collect::<Vec<_>>(),
+            [10, 20, 30, 40, 51]
+        );
+
+        assert_eq!(
+            deque.into_iter().rev().collect::<Vec<_>>(),
+            [51, 40, 30, 20, 10]
+        );
+    }
+
+    #[test]
+    fn traits() {
+        let deque: Deque<String> = ["a", "b"].iter().map(|s| s.to_string()).collect();
+        let mut copy = deque.clone();
+
+        assert_eq!(deque, copy);
+        assert_eq!(format!("{:?}", copy), r#"["a", "b"]"#);
+
+        copy.push_front("z".to_string());
+        assert_ne!(deque, copy);
+        copy.pop_front();
+        copy.extend(["c".to_string()]);
+        assert_ne!(deque, copy);
+        assert_eq!(copy.len(), 3);
+    }
+
+    #[test]
+    fn miri_food() {
+        let mut deque = Deque::new();
+
+        deque.push_back(1);
+        deque.push_front(0);
+        deque.push_back(2);
+
+        if let Some(x) = deque.front_mut() {
+            *x = 10;
+        }
+        if let Some(x) = deque.back_mut() {
+            *x = 20;
+        }
+
+        for elem in &mut deque {
+            *elem += 1;
+        }
+
+        let mut iter = deque.iter();
+        assert_eq!(iter.next(), Some(&11));
+        assert_eq!(iter.next_back(), Some(&21));
+
+        assert_eq!(deque.pop_back(), Some(21));
+        deque.push_front(5);
+
+        // Drop it on the ground and let the dtor exercise itself
+    }
+
+    struct DropCounter<'a> {
+        drops: &'a Cell<usize>,
+        panics: bool,
+    }
+
+    impl Drop for DropCounter<'_> {
+        fn drop(&mut self) {
+            self.drops.set(self.drops.get() + 1);
+
+            if self.panics {
+                panic!("panic in drop");
+            }
+        }
+    }
+
+    #[test]
+    fn panicking_drop() {
+        let drops = Cell::new(0);
+        let mut deque = Deque::new();
+
+        (0..5).for_each(|i| {
+            deque.push_back(DropCounter {
+                drops: &drops,
+                panics: i == 1,
+            })
+        });
+
+        let result = catch_unwind(AssertUnwindSafe(|| drop(deque)));
+
+        assert!(result.is_err());
+        // the elements after the panicking one were still dropped
+        assert_eq!(drops.get(), 5);
+    }
+
+    #[test]
+    fn send_sync() {
+        fn is_send<T: Send>() {}
+        fn is_sync<T: Sync>() {}
+
+        is_send::<Deque<i32>>();
+        is_sync::<Deque<i32>>();
+        is_send::<Iter<'_, i32>>();
+        is_sync::<Iter<'_, i32>>();
+        is_send::<IterMut<'_, i32>>();
+        is_sync::<IterMut<'_, i32>>();
+
+        let mut deque = Deque::new();
+        deque.push_back(1);
+        let deque = std::thread::spawn(move || {
+            deque.push_front(2);
+            deque
+        })
+        .join()
+        .unwrap();
+        assert_eq!(deque.iter().sum::<i32>(), 3);
+    }
+}
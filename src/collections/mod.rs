@@ -0,0 +1,11 @@
+mod bag;
+mod deque;
+mod queue;
+mod randomized_queue;
+mod stack;
+
+pub use bag::*;
+pub use deque::*;
+pub use queue::*;
+pub use randomized_queue::*;
+pub use stack::*;
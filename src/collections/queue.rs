@@ -0,0 +1,242 @@
+use std::fmt::Display;
+
+use crate::singly_linked_list::{self, List};
+
+use super::stack::write_items;
+
+/// FIFO queue on the linked list, enqueueing at the tail and dequeueing at the head.
+pub struct Queue<T> {
+    list: List<T>,
+}
+
+impl<T> Queue<T> {
+    pub fn new() -> Self {
+        Queue { list: List::new() }
+    }
+
+    pub fn enqueue(&mut self, item: T) {
+        self.list.push_back(item);
+    }
+
+    pub fn dequeue(&mut self) -> Option<T> {
+        self.list.pop_front()
+    }
+
+    pub fn peek(&self) -> Option<&T> {
+        self.list.front()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.list.len()
+    }
+
+    /// from least to most recently added
+    pub fn iter(&self) -> singly_linked_list::Iter<'_, T> {
+        self.list.iter()
+    }
+}
+
+impl<T> Default for Queue<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Extend<T> for Queue<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|item| self.enqueue(item));
+    }
+}
+
+impl<T> FromIterator<T> for Queue<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut queue = Queue::new();
+        queue.extend(iter);
+        queue
+    }
+}
+
+impl<T> IntoIterator for Queue<T> {
+    type Item = T;
+
+    type IntoIter = singly_linked_list::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.list.into_iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a Queue<T> {
+    type Item = &'a T;
+
+    type IntoIter = singly_linked_list::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<T: Display> Display for Queue<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write_items(f, self.iter())
+    }
+}
+
+/// FIFO queue on a circular array that doubles when full and halves when a
+/// quarter full.
+pub struct ResizingArrayQueue<T> {
+    items: Vec<Option<T>>,
+    head: usize,
+    len: usize,
+}
+
+impl<T> ResizingArrayQueue<T> {
+    pub fn new() -> Self {
+        ResizingArrayQueue {
+            items: vec![],
+            head: 0,
+            len: 0,
+        }
+    }
+
+    /// unwraps the circular array so the head lands at index 0
+    fn resize(&mut self, capacity: usize) {
+        let old_capacity = self.items.len();
+        let mut items: Vec<Option<T>> = (0..capacity).map(|_| None).collect();
+
+        (0..self.len).for_each(|i| {
+            items[i] = self.items[(self.head + i) % old_capacity].take();
+        });
+
+        self.items = items;
+        self.head = 0;
+    }
+
+    pub fn enqueue(&mut self, item: T) {
+        if self.len == self.items.len() {
+            self.resize((2 * self.items.len()).max(1));
+        }
+
+        let tail = (self.head + self.len) % self.items.len();
+        self.items[tail] = Some(item);
+        self.len += 1;
+    }
+
+    pub fn dequeue(&mut self) -> Option<T> {
+        if self.len == 0 {
+            return None;
+        }
+
+        let item = self.items[self.head].take();
+        self.head = (self.head + 1) % self.items.len();
+        self.len -= 1;
+
+        if self.len > 0 && self.len == self.items.len() / 4 {
+            self.resize(self.items.len() / 2);
+        }
+
+        item
+    }
+
+    pub fn peek(&self) -> Option<&T> {
+        if self.len == 0 {
+            None
+        } else {
+            self.items[self.head].as_ref()
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.items.len()
+    }
+
+    /// from least to most recently added
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        (0..self.len).map(move |i| {
+            self.items[(self.head + i) % self.items.len()]
+                .as_ref()
+                .unwrap()
+        })
+    }
+}
+
+impl<T> Default for ResizingArrayQueue<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Extend<T> for ResizingArrayQueue<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|item| self.enqueue(item));
+    }
+}
+
+impl<T> FromIterator<T> for ResizingArrayQueue<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut queue = ResizingArrayQueue::new();
+        queue.extend(iter);
+        queue
+    }
+}
+
+impl<T: Display> Display for ResizingArrayQueue<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write_items(f, self.iter())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn queue() {
+        let mut queue: Queue<_> = "to be or not to".split(' ').collect();
+
+        assert_eq!(queue.len(), 5);
+        assert_eq!(queue.peek(), Some(&"to"));
+
+        assert_eq!(queue.dequeue(), Some("to"));
+        assert_eq!(queue.dequeue(), Some("be"));
+        queue.enqueue("be");
+        assert_eq!(queue.to_string(), "or not to be");
+        assert_eq!(queue.len(), 4);
+        assert_eq!(
+            queue.into_iter().collect::<Vec<_>>(),
+            ["or", "not", "to", "be"]
+        );
+    }
+
+    #[test]
+    fn resizing_array_queue() {
+        let mut queue = ResizingArrayQueue::new();
+        assert_eq!(queue.dequeue(), None);
+
+        queue.extend(0..4);
+        assert_eq!(queue.dequeue(), Some(0));
+        assert_eq!(queue.dequeue(), Some(1));
+
+        // wraps around the end of the array before growing
+        queue.extend(4..7);
+        assert_eq!(queue.capacity(), 8);
+        assert_eq!(queue.to_string(), "2 3 4 5 6");
+
+        (2..6).for_each(|i| assert_eq!(queue.dequeue(), Some(i)));
+        assert_eq!(queue.capacity(), 2);
+        assert_eq!(queue.peek(), Some(&6));
+        assert_eq!(queue.iter().collect::<Vec<_>>(), [&6]);
+    }
+}
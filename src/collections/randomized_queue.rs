@@ -0,0 +1,163 @@
+use rand::{seq::SliceRandom, thread_rng, Rng};
+
+/// Queue whose dequeue removes an item chosen uniformly at random.
+///
+/// Items live in a resizing array; a random item is swapped with the last one
+/// before removal, so every operation is amortized O(1).
+pub struct RandomizedQueue<T> {
+    items: Vec<T>,
+}
+
+impl<T> RandomizedQueue<T> {
+    pub fn new() -> Self {
+        RandomizedQueue { items: vec![] }
+    }
+
+    pub fn enqueue(&mut self, item: T) {
+        self.items.push(item);
+    }
+
+    pub fn dequeue(&mut self) -> Option<T> {
+        if self.items.is_empty() {
+            return None;
+        }
+
+        let i = thread_rng().gen_range(0..self.items.len());
+
+        Some(self.items.swap_remove(i))
+    }
+
+    /// a random item, without removing it
+    pub fn sample(&self) -> Option<&T> {
+        self.items.choose(&mut thread_rng())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Every call shuffles its own order, so iterators are independent of
+    /// each other.
+    pub fn iter(&self) -> Shuffled<'_, T> {
+        let mut order: Vec<usize> = (0..self.items.len()).collect();
+        order.shuffle(&mut thread_rng());
+
+        Shuffled {
+            items: &self.items,
+            order: order.into_iter(),
+        }
+    }
+}
+
+impl<T> Default for RandomizedQueue<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Extend<T> for RandomizedQueue<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.items.extend(iter);
+    }
+}
+
+impl<T> FromIterator<T> for RandomizedQueue<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        RandomizedQueue {
+            items: iter.into_iter().collect(),
+        }
+    }
+}
+
+pub struct Shuffled<'a, T> {
+    items: &'a [T],
+    order: std::vec::IntoIter<usize>,
+}
+
+impl<'a, T> Iterator for Shuffled<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.order.next().map(|i| &self.items[i])
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.order.size_hint()
+    }
+}
+
+impl<T> ExactSizeIterator for Shuffled<'_, T> {}
+
+impl<'a, T> IntoIterator for &'a RandomizedQueue<T> {
+    type Item = &'a T;
+
+    type IntoIter = Shuffled<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dequeue_is_uniform() {
+        let trials = 6000;
+        let mut counts = [0; 3];
+
+        (0..trials).for_each(|_| {
+            let mut queue: RandomizedQueue<usize> = (0..3).collect();
+            counts[queue.dequeue().unwrap()] += 1;
+        });
+
+        // each expected 2000 with a standard deviation of about 37
+        assert!(counts.iter().all(|&count| (1700..2300).contains(&count)));
+    }
+
+    #[test]
+    fn drains_every_item() {
+        let mut queue: RandomizedQueue<i32> = (0..100).collect();
+        queue.enqueue(100);
+        assert_eq!(queue.len(), 101);
+        assert!(queue.sample().is_some());
+
+        let mut items: Vec<i32> = std::iter::from_fn(|| queue.dequeue()).collect();
+        items.sort();
+
+        assert_eq!(items, (0..=100).collect::<Vec<_>>());
+        assert!(queue.is_empty());
+        assert_eq!(queue.sample(), None);
+    }
+
+    #[test]
+    fn independent_iterators() {
+        let queue: RandomizedQueue<i32> = (0..10).collect();
+
+        // nested iteration must not disturb the outer order
+        let outer: Vec<i32> = queue
+            .iter()
+            .map(|&x| {
+                let mut inner: Vec<i32> = queue.iter().copied().collect();
+                inner.sort();
+                assert_eq!(inner, (0..10).collect::<Vec<_>>());
+                x
+            })
+            .collect();
+
+        let mut sorted = outer.clone();
+        sorted.sort();
+        assert_eq!(sorted, (0..10).collect::<Vec<_>>());
+
+        // 10! orders, two iterators agreeing by chance is negligible
+        let a: Vec<&i32> = queue.iter().collect();
+        let b: Vec<&i32> = queue.iter().collect();
+        let c: Vec<&i32> = queue.iter().collect();
+        assert!(a != b || b != c);
+    }
+}
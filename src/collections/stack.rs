@@ -0,0 +1,242 @@
+use std::fmt::Display;
+
+use crate::singly_linked_list::{self, List};
+
+/// LIFO stack on the linked list, pushing and popping at the head.
+pub struct Stack<T> {
+    list: List<T>,
+}
+
+impl<T> Stack<T> {
+    pub fn new() -> Self {
+        Stack { list: List::new() }
+    }
+
+    pub fn push(&mut self, item: T) {
+        self.list.push_front(item);
+    }
+
+    pub fn pop(&mut self) -> Option<T> {
+        self.list.pop_front()
+    }
+
+    pub fn peek(&self) -> Option<&T> {
+        self.list.front()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.list.len()
+    }
+
+    /// from top to bottom
+    pub fn iter(&self) -> singly_linked_list::Iter<'_, T> {
+        self.list.iter()
+    }
+}
+
+impl<T> Default for Stack<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Extend<T> for Stack<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|item| self.push(item));
+    }
+}
+
+impl<T> FromIterator<T> for Stack<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut stack = Stack::new();
+        stack.extend(iter);
+        stack
+    }
+}
+
+impl<T> IntoIterator for Stack<T> {
+    type Item = T;
+
+    type IntoIter = singly_linked_list::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.list.into_iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a Stack<T> {
+    type Item = &'a T;
+
+    type IntoIter = singly_linked_list::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<T: Display> Display for Stack<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write_items(f, self.iter())
+    }
+}
+
+/// items separated by a space, as the book's toString does
+pub(super) fn write_items<'a, T: Display + 'a>(
+    f: &mut std::fmt::Formatter<'_>,
+    items: impl Iterator<Item = &'a T>,
+) -> std::fmt::Result {
+    items.enumerate().try_for_each(|(i, item)| {
+        if i > 0 {
+            write!(f, " ")?;
+        }
+        write!(f, "{}", item)
+    })
+}
+
+/// LIFO stack on an array that doubles when full and halves when a quarter full.
+pub struct ResizingArrayStack<T> {
+    items: Vec<Option<T>>,
+    len: usize,
+}
+
+impl<T> ResizingArrayStack<T> {
+    pub fn new() -> Self {
+        ResizingArrayStack {
+            items: vec![],
+            len: 0,
+        }
+    }
+
+    fn resize(&mut self, capacity: usize) {
+        let mut items: Vec<Option<T>> = (0..capacity).map(|_| None).collect();
+
+        self.items[..self.len]
+            .iter_mut()
+            .zip(items.iter_mut())
+            .for_each(|(old, new)| *new = old.take());
+
+        self.items = items;
+    }
+
+    pub fn push(&mut self, item: T) {
+        if self.len == self.items.len() {
+            self.resize((2 * self.items.len()).max(1));
+        }
+
+        self.items[self.len] = Some(item);
+        self.len += 1;
+    }
+
+    pub fn pop(&mut self) -> Option<T> {
+        if self.len == 0 {
+            return None;
+        }
+
+        self.len -= 1;
+        let item = self.items[self.len].take();
+
+        if self.len > 0 && self.len == self.items.len() / 4 {
+            self.resize(self.items.len() / 2);
+        }
+
+        item
+    }
+
+    pub fn peek(&self) -> Option<&T> {
+        self.len.checked_sub(1).and_then(|i| self.items[i].as_ref())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.items.len()
+    }
+
+    /// from top to bottom
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.items[..self.len]
+            .iter()
+            .rev()
+            .map(|item| item.as_ref().unwrap())
+    }
+}
+
+impl<T> Default for ResizingArrayStack<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Extend<T> for ResizingArrayStack<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|item| self.push(item));
+    }
+}
+
+impl<T> FromIterator<T> for ResizingArrayStack<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut stack = ResizingArrayStack::new();
+        stack.extend(iter);
+        stack
+    }
+}
+
+impl<T: Display> Display for ResizingArrayStack<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write_items(f, self.iter())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stack() {
+        let mut stack: Stack<_> = "to be or not to".split(' ').collect();
+
+        assert_eq!(stack.len(), 5);
+        assert_eq!(stack.peek(), Some(&"to"));
+        assert_eq!(stack.to_string(), "to not or be to");
+
+        assert_eq!(stack.pop(), Some("to"));
+        assert_eq!(stack.pop(), Some("not"));
+        stack.push("be");
+        assert_eq!(stack.to_string(), "be or be to");
+        assert_eq!(
+            stack.into_iter().collect::<Vec<_>>(),
+            ["be", "or", "be", "to"]
+        );
+    }
+
+    #[test]
+    fn resizing_array_stack() {
+        let mut stack = ResizingArrayStack::new();
+        assert_eq!(stack.pop(), None);
+        assert_eq!(stack.peek(), None);
+
+        stack.extend(0..10);
+        assert_eq!(stack.len(), 10);
+        assert_eq!(stack.capacity(), 16);
+        assert_eq!(stack.peek(), Some(&9));
+
+        (0..7).rev().for_each(|i| {
+            assert_eq!(stack.pop(), Some(i + 3));
+        });
+        assert_eq!(stack.capacity(), 8);
+        assert_eq!(stack.to_string(), "2 1 0");
+
+        stack.push(5);
+        assert_eq!(stack.iter().copied().collect::<Vec<_>>(), [5, 2, 1, 0]);
+    }
+}
@@ -0,0 +1,217 @@
+use std::time::{Duration, Instant};
+
+#[derive(Debug)]
+pub struct DoublingRound {
+    pub size: usize,
+    pub duration: Duration,
+    /// time of this round divided by time of the previous one, None for the
+    /// first round or after one too fast to measure
+    pub ratio: Option<f64>,
+}
+
+#[derive(Debug)]
+pub struct DoublingReport {
+    pub rounds: Vec<DoublingRound>,
+    /// (a, b) in T(N) = a * N^b with a in seconds, None if the rounds
+    /// don't determine a fit
+    pub fit: Option<(f64, f64)>,
+}
+
+impl DoublingReport {
+    /// b in T(N) = a * N^b
+    pub fn exponent(&self) -> Option<f64> {
+        self.fit.map(|(_, b)| b)
+    }
+
+    /// extrapolated running time for an input of size n, None without a fit
+    /// or if it doesn't fit in a Duration
+    pub fn predict(&self, size: usize) -> Option<Duration> {
+        let (a, b) = self.fit?;
+
+        Duration::try_from_secs_f64(a * (size as f64).powf(b)).ok()
+    }
+}
+
+/// Fit T(N) = a * N^b by least squares on lg T = lg a + b lg N.
+///
+/// Samples with a non-positive size or time are skipped since they have no
+/// logarithm. Returns (a, b), or None if fewer than two distinct sizes remain.
+pub fn fit_power_law(samples: &[(f64, f64)]) -> Option<(f64, f64)> {
+    let points: Vec<(f64, f64)> = samples
+        .iter()
+        .filter(|(n, t)| *n > 0.0 && *t > 0.0)
+        .map(|(n, t)| (n.log2(), t.log2()))
+        .collect();
+
+    if points.len() < 2 {
+        return None;
+    }
+
+    let len = points.len() as f64;
+    let mean_x = points.iter().map(|(x, _)| x).sum::<f64>() / len;
+    let mean_y = points.iter().map(|(_, y)| y).sum::<f64>() / len;
+
+    let sxx: f64 = points.iter().map(|(x, _)| (x - mean_x).powi(2)).sum();
+    let sxy: f64 = points
+        .iter()
+        .map(|(x, y)| (x - mean_x) * (y - mean_y))
+        .sum();
+
+    if sxx == 0.0 {
+        return None;
+    }
+
+    let b = sxy / sxx;
+    let lg_a = mean_y - b * mean_x;
+
+    Some((lg_a.exp2(), b))
+}
+
+/// duration / prev, None if prev is too short to have been measured
+fn ratio(prev: Duration, duration: Duration) -> Option<f64> {
+    (!prev.is_zero()).then(|| duration.as_secs_f64() / prev.as_secs_f64())
+}
+
+/// Run `f` on inputs of size start, 2 * start, 4 * start ... for `rounds`
+/// rounds, or fewer if the next size would overflow a usize. `generate`
+/// builds a fresh input of the given size, which is not included in the
+/// timing.
+pub fn doubling_test<I, G, F>(
+    start: usize,
+    rounds: usize,
+    mut generate: G,
+    mut f: F,
+) -> DoublingReport
+where
+    G: FnMut(usize) -> I,
+    F: FnMut(&mut I),
+{
+    let mut result: Vec<DoublingRound> = Vec::with_capacity(rounds);
+    let mut size = start;
+
+    for _ in 0..rounds {
+        let mut input = generate(size);
+
+        let instant = Instant::now();
+        f(&mut input);
+        let duration = instant.elapsed();
+
+        let ratio = result
+            .last()
+            .and_then(|prev| ratio(prev.duration, duration));
+
+        result.push(DoublingRound {
+            size,
+            duration,
+            ratio,
+        });
+
+        match size.checked_mul(2) {
+            Some(next) => size = next,
+            None => break,
+        }
+    }
+
+    let samples: Vec<(f64, f64)> = result
+        .iter()
+        .map(|round| (round.size as f64, round.duration.as_secs_f64()))
+        .collect();
+
+    DoublingReport {
+        rounds: result,
+        fit: fit_power_law(&samples),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fit() {
+        let samples: Vec<(f64, f64)> = [250.0, 500.0, 1000.0, 2000.0]
+            .iter()
+            .map(|&n: &f64| (n, 1e-9 * n.powi(3)))
+            .collect();
+
+        let (a, b) = fit_power_law(&samples).unwrap();
+        assert!((b - 3.0).abs() < 1e-9);
+        assert!((a - 1e-9).abs() < 1e-15);
+
+        assert_eq!(fit_power_law(&[(100.0, 1.0)]), None);
+        assert_eq!(fit_power_law(&[(100.0, 1.0), (100.0, 2.0)]), None);
+    }
+
+    #[test]
+    fn rounds() {
+        // the work is the same whatever the timings, only its shape is checked
+        let mut seen = Vec::new();
+        let report = doubling_test(
+            1000,
+            4,
+            |n| (0..n).collect::<Vec<usize>>(),
+            |list| {
+                list.sort_unstable_by(|a, b| b.cmp(a));
+                seen.push(list.len());
+            },
+        );
+
+        assert_eq!(seen, [1000, 2000, 4000, 8000]);
+        assert_eq!(
+            report
+                .rounds
+                .iter()
+                .map(|round| round.size)
+                .collect::<Vec<_>>(),
+            seen
+        );
+        assert!(report.rounds[0].ratio.is_none());
+    }
+
+    #[test]
+    fn stops_before_overflow() {
+        let start = usize::MAX / 4 + 1;
+        let report = doubling_test(start, 5, |n| n, |_| {});
+
+        assert_eq!(
+            report
+                .rounds
+                .iter()
+                .map(|round| round.size)
+                .collect::<Vec<_>>(),
+            [start, 2 * start]
+        );
+    }
+
+    #[test]
+    fn predict() {
+        let report = DoublingReport {
+            rounds: Vec::new(),
+            fit: Some((1e-9, 2.0)),
+        };
+
+        assert_eq!(report.exponent(), Some(2.0));
+        let predicted = report.predict(1000).unwrap().as_secs_f64();
+        assert!((predicted - 1e-3).abs() < 1e-12);
+        assert_eq!(report.predict(usize::MAX), None);
+    }
+
+    #[test]
+    fn unmeasurable_rounds() {
+        assert_eq!(ratio(Duration::ZERO, Duration::from_millis(1)), None);
+        assert_eq!(ratio(Duration::ZERO, Duration::ZERO), None);
+        assert_eq!(
+            ratio(Duration::from_millis(2), Duration::from_millis(5)),
+            Some(2.5)
+        );
+
+        let report = DoublingReport {
+            rounds: Vec::new(),
+            fit: fit_power_law(&[(1.0, 0.0), (2.0, 0.0)]),
+        };
+
+        assert!(report.fit.is_none());
+        assert_eq!(report.exponent(), None);
+        assert_eq!(report.predict(1000), None);
+    }
+}
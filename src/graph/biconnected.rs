@@ -0,0 +1,374 @@
+use std::{collections::HashSet, ops::ControlFlow};
+
+use crate::union_find::{UnionFind, WeightedQuickUnion};
+
+use super::{
+    search::{depth_first, DfsEvent},
+    undirected_graph::Graph,
+};
+
+/// Single points of failure of an undirected graph, from one depth-first
+/// search with Tarjan's low-link numbers: `low[v]` is the smallest preorder
+/// number reachable from the subtree of v using at most one back edge.
+///
+/// Parallel edges are not bridges of each other. Self-loops never
+/// disconnect anything and belong to no biconnected component.
+#[derive(Debug)]
+pub struct Biconnected {
+    bridges: Vec<(usize, usize)>,
+    articulation: Vec<bool>,
+    components: Vec<Vec<(usize, usize)>>,
+    /// 2-edge-connected component of each vertex
+    edge_component: Vec<usize>,
+    edge_component_count: usize,
+}
+
+impl Biconnected {
+    pub fn new(g: &Graph) -> Self {
+        let num_vertices = g.num_vertices();
+        let mut marked = vec![false; num_vertices];
+        let mut pre = vec![0; num_vertices];
+        let mut low = vec![0; num_vertices];
+        let mut parent: Vec<Option<usize>> = vec![None; num_vertices];
+        // whether v has already passed over the tree edge back to its parent
+        let mut skipped_parent = vec![false; num_vertices];
+        let mut children = vec![0; num_vertices];
+        let mut counter = 0;
+
+        let mut bridges = Vec::new();
+        let mut articulation = vec![false; num_vertices];
+        let mut components = Vec::new();
+        let mut edges: Vec<(usize, usize)> = Vec::new();
+
+        (0..num_vertices).for_each(|s| {
+            depth_first(g, s, &mut marked, |event| {
+                match event {
+                    DfsEvent::Discover(v) => {
+                        pre[v] = counter;
+                        low[v] = counter;
+                        counter += 1;
+                    }
+                    DfsEvent::TreeEdge(v, w) => {
+                        parent[w] = Some(v);
+                        children[v] += 1;
+                        edges.push((v, w));
+                    }
+                    DfsEvent::NonTreeEdge(v, w) if parent[v] == Some(w) && !skipped_parent[v] => {
+                        skipped_parent[v] = true;
+                    }
+                    // a back edge seen from the deeper end; the other end
+                    // sees it again with pre[w] > pre[v] and skips it
+                    DfsEvent::NonTreeEdge(v, w) if pre[w] < pre[v] => {
+                        low[v] = low[v].min(pre[w]);
+                        edges.push((v, w));
+                    }
+                    DfsEvent::NonTreeEdge(..) => {}
+                    DfsEvent::Finish(w) => {
+                        if let Some(v) = parent[w] {
+                            low[v] = low[v].min(low[w]);
+
+                            if low[w] > pre[v] {
+                                bridges.push((v, w));
+                            }
+
+                            if low[w] >= pre[v] {
+                                if parent[v].is_some() {
+                                    articulation[v] = true;
+                                }
+
+                                let start = edges.iter().rposition(|&e| e == (v, w)).unwrap();
+                                components.push(edges.split_off(start));
+                            }
+                        } else if children[w] > 1 {
+                            articulation[w] = true;
+                        }
+                    }
+                }
+
+                ControlFlow::Continue(())
+            });
+        });
+
+        let (edge_component, edge_component_count) = Self::edge_components(g, &bridges);
+
+        Biconnected {
+            bridges,
+            articulation,
+            components,
+            edge_component,
+            edge_component_count,
+        }
+    }
+
+    /// Connected components once the bridges are gone.
+    fn edge_components(g: &Graph, bridges: &[(usize, usize)]) -> (Vec<usize>, usize) {
+        let bridges: HashSet<(usize, usize)> =
+            bridges.iter().map(|&(v, w)| (v.min(w), v.max(w))).collect();
+
+        let mut uf = WeightedQuickUnion::new(g.num_vertices());
+        (0..g.num_vertices()).for_each(|v| {
+            g.adj(v)
+                .filter(|&&w| !bridges.contains(&(v.min(w), v.max(w))))
+                .for_each(|&w| {
+                    uf.union(v, w);
+                });
+        });
+
+        let mut id = vec![0; g.num_vertices()];
+        uf.components()
+            .iter()
+            .enumerate()
+            .for_each(|(i, members)| members.iter().for_each(|&v| id[v] = i));
+
+        (id, uf.count())
+    }
+
+    /// edges whose removal disconnects their endpoints, as (parent, child)
+    /// in the search tree
+    pub fn bridges(&self) -> &[(usize, usize)] {
+        &self.bridges
+    }
+
+    pub fn is_articulation_point(&self, v: usize) -> bool {
+        self.articulation[v]
+    }
+
+    /// vertices whose removal disconnects their component
+    pub fn articulation_points(&self) -> Vec<usize> {
+        (0..self.articulation.len())
+            .filter(|&v| self.articulation[v])
+            .collect()
+    }
+
+    /// edge sets of the biconnected components, maximal sets of edges in
+    /// which every two edges lie on a common simple cycle
+    pub fn biconnected_components(&self) -> &[Vec<(usize, usize)>] {
+        &self.components
+    }
+
+    /// 2-edge-connected component of v, what stays connected to v after
+    /// removing any single edge
+    pub fn edge_component(&self, v: usize) -> usize {
+        self.edge_component[v]
+    }
+
+    /// vertices of every 2-edge-connected component, ordered by their
+    /// smallest vertex
+    pub fn two_edge_connected_components(&self) -> Vec<Vec<usize>> {
+        let mut components = vec![Vec::new(); self.edge_component_count];
+
+        self.edge_component
+            .iter()
+            .enumerate()
+            .for_each(|(v, &id)| components[id].push(v));
+
+        components
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{thread_rng, Rng};
+
+    use crate::graph::{
+        connected_components::ConnectedComponents,
+        undirected_graph::{create_graph_from_path, read_graph},
+        TINY_G,
+    };
+
+    use super::*;
+
+    fn key((v, w): (usize, usize)) -> (usize, usize) {
+        (v.min(w), v.max(w))
+    }
+
+    fn normalize(edges: &[(usize, usize)]) -> Vec<(usize, usize)> {
+        let mut edges: Vec<(usize, usize)> = edges.iter().copied().map(key).collect();
+        edges.sort_unstable();
+        edges
+    }
+
+    /// every edge once, self-loops and parallel edges included
+    fn edge_list(g: &Graph) -> Vec<(usize, usize)> {
+        let mut edges: Vec<(usize, usize)> = (0..g.num_vertices())
+            .flat_map(|v| g.adj(v).filter(move |&&w| v < w).map(move |&w| (v, w)))
+            .collect();
+
+        // a self-loop shows up twice in its own adjacency list
+        (0..g.num_vertices()).for_each(|v| {
+            (0..g.adj(v).filter(|&&w| w == v).count() / 2).for_each(|_| edges.push((v, v)))
+        });
+
+        edges
+    }
+
+    fn graph_without(
+        num_vertices: usize,
+        edges: &[(usize, usize)],
+        skip: impl Fn(usize, (usize, usize)) -> bool,
+    ) -> Graph {
+        let mut g = Graph::new(num_vertices);
+        edges
+            .iter()
+            .enumerate()
+            .filter(|&(i, &e)| !skip(i, e))
+            .for_each(|(_, &(v, w))| g.add_edge(v, w));
+        g
+    }
+
+    /// Check everything against removing each edge and each vertex in turn.
+    fn assert_oracle(g: &Graph) {
+        let n = g.num_vertices();
+        let edges = edge_list(g);
+        let count = ConnectedComponents::new(g).count();
+        let bc = Biconnected::new(g);
+
+        let bridges: Vec<(usize, usize)> = (0..edges.len())
+            .filter(|&i| {
+                ConnectedComponents::new(&graph_without(n, &edges, |j, _| i == j)).count() > count
+            })
+            .map(|i| edges[i])
+            .collect();
+        assert_eq!(normalize(bc.bridges()), normalize(&bridges));
+
+        // without x's edges, x is isolated and adds a component of its own
+        let without_vertex: Vec<ConnectedComponents> = (0..n)
+            .map(|x| {
+                ConnectedComponents::new(&graph_without(n, &edges, |_, (v, w)| v == x || w == x))
+            })
+            .collect();
+        (0..n).for_each(|x| {
+            assert_eq!(
+                bc.is_articulation_point(x),
+                without_vertex[x].count() - 1 > count
+            );
+        });
+
+        // two edges share a biconnected component iff no vertex separates them
+        let component_of = |e: (usize, usize)| {
+            let e = key(e);
+            let found: Vec<usize> = bc
+                .biconnected_components()
+                .iter()
+                .enumerate()
+                .filter(|(_, c)| normalize(c).contains(&e))
+                .map(|(i, _)| i)
+                .collect();
+            assert_eq!(
+                found.len(),
+                1,
+                "{:?} in {:?}",
+                e,
+                bc.biconnected_components()
+            );
+            found[0]
+        };
+        let proper: Vec<(usize, usize)> = edges.iter().copied().filter(|&(v, w)| v != w).collect();
+        proper.iter().for_each(|&e| {
+            proper.iter().for_each(|&f| {
+                let together = (0..n).all(|x| {
+                    let ends_e = [e.0, e.1].into_iter().filter(|&v| v != x);
+                    ends_e.clone().all(|a| {
+                        [f.0, f.1]
+                            .into_iter()
+                            .filter(|&v| v != x)
+                            .all(|b| without_vertex[x].connected(a, b))
+                    })
+                });
+                assert_eq!(
+                    component_of(e) == component_of(f),
+                    together,
+                    "{:?} {:?}",
+                    e,
+                    f
+                );
+            });
+        });
+        let total: usize = bc.biconnected_components().iter().map(Vec::len).sum();
+        assert_eq!(total, proper.len());
+
+        // two vertices share a 2-edge-connected component iff no edge separates them
+        let without_edge: Vec<ConnectedComponents> = (0..edges.len())
+            .map(|i| ConnectedComponents::new(&graph_without(n, &edges, |j, _| i == j)))
+            .collect();
+        let cc = ConnectedComponents::new(g);
+        (0..n).for_each(|v| {
+            (0..n).for_each(|w| {
+                let together = cc.connected(v, w) && without_edge.iter().all(|c| c.connected(v, w));
+                assert_eq!(bc.edge_component(v) == bc.edge_component(w), together);
+            });
+        });
+    }
+
+    #[test]
+    fn tiny_g() {
+        let g = read_graph(TINY_G.as_bytes()).unwrap();
+        let bc = Biconnected::new(&g);
+
+        assert_eq!(normalize(bc.bridges()), [(0, 1), (0, 2), (7, 8), (9, 10)]);
+        assert_eq!(bc.articulation_points(), [0, 9]);
+        assert_eq!(bc.biconnected_components().len(), 6);
+        assert_eq!(
+            bc.two_edge_connected_components(),
+            [
+                vec![0, 3, 4, 5, 6],
+                vec![1],
+                vec![2],
+                vec![7],
+                vec![8],
+                vec![9, 11, 12],
+                vec![10]
+            ]
+        );
+
+        assert_oracle(&g);
+    }
+
+    #[test]
+    fn parallel_edges_and_self_loops() {
+        let g = read_graph("3 4  0 1  1 0  1 2  2 2".as_bytes()).unwrap();
+        let bc = Biconnected::new(&g);
+
+        assert_eq!(normalize(bc.bridges()), [(1, 2)]);
+        assert_eq!(bc.articulation_points(), [1]);
+        assert_eq!(bc.biconnected_components().len(), 2);
+
+        assert_oracle(&g);
+    }
+
+    #[test]
+    fn random() {
+        let mut rng = thread_rng();
+
+        (0..200).for_each(|_| {
+            let n = rng.gen_range(1..10);
+            let mut g = Graph::new(n);
+            (0..rng.gen_range(0..2 * n)).for_each(|_| {
+                g.add_edge(rng.gen_range(0..n), rng.gen_range(0..n));
+            });
+
+            assert_oracle(&g);
+        });
+    }
+
+    #[test]
+    fn medium_g() {
+        let g = create_graph_from_path("./algs4-data/mediumG.txt");
+        let bc = Biconnected::new(&g);
+
+        println!("bridges: {:?}", bc.bridges());
+        println!("articulation points: {:?}", bc.articulation_points());
+        println!(
+            "{} biconnected components",
+            bc.biconnected_components().len()
+        );
+
+        let edges = edge_list(&g);
+        let count = ConnectedComponents::new(&g).count();
+        bc.bridges().iter().for_each(|&bridge| {
+            let i = edges.iter().position(|&e| key(e) == key(bridge)).unwrap();
+            let rest = graph_without(g.num_vertices(), &edges, |j, _| i == j);
+            assert!(ConnectedComponents::new(&rest).count() > count);
+        });
+    }
+}
@@ -0,0 +1,124 @@
+use std::ops::ControlFlow;
+
+use super::{
+    cycle::path_up,
+    search::{depth_first, DfsEvent},
+    undirected_graph::Graph,
+};
+
+/// Two-colors an undirected graph so that every edge joins different
+/// colors, or finds an odd-length cycle proving that no such coloring exists.
+#[derive(Debug)]
+pub struct Bipartite {
+    color: Vec<bool>,
+    odd_cycle: Option<Vec<usize>>,
+}
+
+impl Bipartite {
+    pub fn new(g: &Graph) -> Self {
+        let num_vertices = g.num_vertices();
+        let mut marked = vec![false; num_vertices];
+        let mut edge_to: Vec<Option<usize>> = vec![None; num_vertices];
+        let mut color = vec![false; num_vertices];
+        let mut odd_cycle = None;
+
+        (0..num_vertices).try_for_each(|s| {
+            depth_first(g, s, &mut marked, |event| match event {
+                DfsEvent::TreeEdge(v, w) => {
+                    edge_to[w] = Some(v);
+                    color[w] = !color[v];
+                    ControlFlow::Continue(())
+                }
+                // the first conflict is seen from the deeper end, so w is
+                // an ancestor of v and the tree path closes an odd cycle
+                DfsEvent::NonTreeEdge(v, w) if color[v] == color[w] => {
+                    odd_cycle = Some(path_up(&edge_to, v, w));
+                    ControlFlow::Break(())
+                }
+                _ => ControlFlow::Continue(()),
+            })
+        });
+
+        Bipartite { color, odd_cycle }
+    }
+
+    pub fn is_bipartite(&self) -> bool {
+        self.odd_cycle.is_none()
+    }
+
+    /// side of v, only meaningful when the graph is bipartite
+    pub fn color(&self, v: usize) -> bool {
+        self.color[v]
+    }
+
+    /// the vertices of each color, if the graph is bipartite
+    pub fn partition(&self) -> Option<(Vec<usize>, Vec<usize>)> {
+        self.is_bipartite()
+            .then(|| (0..self.color.len()).partition(|&v| !self.color[v]))
+    }
+
+    /// closed walk with an odd number of edges, if the graph is not bipartite
+    pub fn odd_cycle(&self) -> Option<&[usize]> {
+        self.odd_cycle.as_deref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{thread_rng, Rng};
+
+    use crate::graph::undirected_graph::read_graph;
+
+    use super::*;
+
+    fn assert_certificate(g: &Graph, bipartite: &Bipartite) {
+        match (bipartite.partition(), bipartite.odd_cycle()) {
+            (Some((left, right)), None) => {
+                assert_eq!(left.len() + right.len(), g.num_vertices());
+                (0..g.num_vertices()).for_each(|v| {
+                    g.adj(v)
+                        .for_each(|&w| assert_ne!(bipartite.color(v), bipartite.color(w)))
+                });
+            }
+            (None, Some(cycle)) => {
+                assert_eq!(cycle.first(), cycle.last());
+                assert_eq!(cycle.len() % 2, 0, "{:?} has an even length", cycle);
+                cycle
+                    .windows(2)
+                    .for_each(|e| assert!(g.adj(e[0]).any(|&w| w == e[1])));
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn small() {
+        let g = read_graph("4 4  0 1  1 2  2 3  3 0".as_bytes()).unwrap();
+        let b = Bipartite::new(&g);
+        assert_eq!(b.partition(), Some((vec![0, 2], vec![1, 3])));
+
+        let g = read_graph("5 5  0 1  1 2  2 3  3 4  4 0".as_bytes()).unwrap();
+        let b = Bipartite::new(&g);
+        assert!(!b.is_bipartite());
+        assert_eq!(b.odd_cycle().unwrap().len(), 6);
+        assert_certificate(&g, &b);
+
+        let g = read_graph("2 1  1 1".as_bytes()).unwrap();
+        assert_eq!(Bipartite::new(&g).odd_cycle(), Some(&[1, 1][..]));
+    }
+
+    #[test]
+    fn random() {
+        let mut rng = thread_rng();
+
+        (0..200).for_each(|_| {
+            let n = rng.gen_range(1..20);
+            let mut g = Graph::new(n);
+            (0..rng.gen_range(0..2 * n)).for_each(|_| {
+                g.add_edge(rng.gen_range(0..n), rng.gen_range(0..n));
+            });
+
+            assert_certificate(&g, &Bipartite::new(&g));
+        });
+    }
+}
@@ -0,0 +1,110 @@
+use std::ops::ControlFlow;
+
+use super::{
+    search::{depth_first, DfsEvent},
+    undirected_graph::Graph,
+};
+
+/// Connected components of an undirected graph, numbered 0 to count - 1 in
+/// order of their smallest vertex.
+#[derive(Debug)]
+pub struct ConnectedComponents {
+    id: Vec<usize>,
+    size: Vec<usize>,
+}
+
+impl ConnectedComponents {
+    pub fn new(g: &Graph) -> Self {
+        let num_vertices = g.num_vertices();
+        let mut marked = vec![false; num_vertices];
+        let mut id = vec![0; num_vertices];
+        let mut size = Vec::new();
+
+        (0..num_vertices).for_each(|s| {
+            if marked[s] {
+                return;
+            }
+
+            let component = size.len();
+            size.push(0);
+
+            depth_first(g, s, &mut marked, |event| {
+                if let DfsEvent::Discover(v) = event {
+                    id[v] = component;
+                    size[component] += 1;
+                }
+
+                ControlFlow::Continue(())
+            });
+        });
+
+        ConnectedComponents { id, size }
+    }
+
+    /// number of components
+    pub fn count(&self) -> usize {
+        self.size.len()
+    }
+
+    /// component of v
+    pub fn id(&self, v: usize) -> usize {
+        self.id[v]
+    }
+
+    pub fn connected(&self, v: usize, w: usize) -> bool {
+        self.id[v] == self.id[w]
+    }
+
+    /// number of vertices in the component of v
+    pub fn size(&self, v: usize) -> usize {
+        self.size[self.id[v]]
+    }
+
+    /// vertices of every component, indexed by id
+    pub fn components(&self) -> Vec<Vec<usize>> {
+        let mut components: Vec<Vec<usize>> = self
+            .size
+            .iter()
+            .map(|&size| Vec::with_capacity(size))
+            .collect();
+
+        self.id
+            .iter()
+            .enumerate()
+            .for_each(|(v, &id)| components[id].push(v));
+
+        components
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::graph::{undirected_graph::read_graph, TINY_G};
+
+    use super::*;
+
+    #[test]
+    fn tiny_g() {
+        let g = read_graph(TINY_G.as_bytes()).unwrap();
+        let cc = ConnectedComponents::new(&g);
+
+        assert_eq!(cc.count(), 3);
+        assert_eq!(
+            cc.components(),
+            [vec![0, 1, 2, 3, 4, 5, 6], vec![7, 8], vec![9, 10, 11, 12]]
+        );
+        assert!(cc.connected(3, 6));
+        assert!(!cc.connected(6, 7));
+        assert_eq!(cc.id(10), 2);
+        assert_eq!(cc.size(12), 4);
+    }
+
+    #[test]
+    fn isolated_vertices() {
+        let cc = ConnectedComponents::new(&Graph::new(3));
+        assert_eq!(cc.count(), 3);
+        assert_eq!(cc.components(), [vec![0], vec![1], vec![2]]);
+
+        assert_eq!(ConnectedComponents::new(&Graph::new(0)).count(), 0);
+    }
+}
@@ -0,0 +1,170 @@
+use std::ops::ControlFlow;
+
+use super::{
+    search::{depth_first, DfsEvent},
+    undirected_graph::Graph,
+};
+
+/// Finds a cycle in an undirected graph. A self-loop v-v is a cycle of
+/// length 1 and two parallel edges v-w a cycle of length 2.
+#[derive(Debug)]
+pub struct Cycle {
+    cycle: Option<Vec<usize>>,
+}
+
+impl Cycle {
+    pub fn new(g: &Graph) -> Self {
+        let cycle = Self::self_loop(g)
+            .or_else(|| Self::parallel_edges(g))
+            .or_else(|| Self::search(g));
+
+        Cycle { cycle }
+    }
+
+    fn self_loop(g: &Graph) -> Option<Vec<usize>> {
+        (0..g.num_vertices()).find_map(|v| g.adj(v).any(|&w| w == v).then(|| vec![v, v]))
+    }
+
+    fn parallel_edges(g: &Graph) -> Option<Vec<usize>> {
+        let mut marked = vec![false; g.num_vertices()];
+
+        (0..g.num_vertices()).find_map(|v| {
+            let parallel = g.adj(v).find(|&&w| std::mem::replace(&mut marked[w], true));
+            g.adj(v).for_each(|&w| marked[w] = false);
+
+            parallel.map(|&w| vec![v, w, v])
+        })
+    }
+
+    /// Without self-loops or parallel edges, the first edge to a marked
+    /// vertex other than the parent goes back to an ancestor on the stack.
+    fn search(g: &Graph) -> Option<Vec<usize>> {
+        let num_vertices = g.num_vertices();
+        let mut marked = vec![false; num_vertices];
+        let mut edge_to: Vec<Option<usize>> = vec![None; num_vertices];
+        let mut cycle = None;
+
+        (0..num_vertices).try_for_each(|s| {
+            depth_first(g, s, &mut marked, |event| match event {
+                DfsEvent::TreeEdge(v, w) => {
+                    edge_to[w] = Some(v);
+                    ControlFlow::Continue(())
+                }
+                DfsEvent::NonTreeEdge(v, w) if edge_to[v] != Some(w) => {
+                    cycle = Some(path_up(&edge_to, v, w));
+                    ControlFlow::Break(())
+                }
+                _ => ControlFlow::Continue(()),
+            })
+        });
+
+        cycle
+    }
+
+    pub fn has_cycle(&self) -> bool {
+        self.cycle.is_some()
+    }
+
+    /// vertices of the cycle, starting and ending at the same vertex
+    pub fn cycle(&self) -> Option<&[usize]> {
+        self.cycle.as_deref()
+    }
+}
+
+/// Closed walk w -> ... -> v -> w following tree edges from the ancestor w
+/// down to v, then the edge v-w.
+pub(super) fn path_up(edge_to: &[Option<usize>], v: usize, w: usize) -> Vec<usize> {
+    let mut path = vec![w];
+
+    let mut x = v;
+    while x != w {
+        path.push(x);
+        x = edge_to[x].unwrap();
+    }
+
+    path[1..].reverse();
+    path.push(w);
+
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{thread_rng, Rng};
+
+    use crate::{
+        graph::{undirected_graph::read_graph, TINY_G},
+        union_find::{UnionFind, WeightedQuickUnion},
+    };
+
+    use super::*;
+
+    /// Panic unless `cycle` is a closed walk along edges of g that repeats
+    /// no vertex.
+    fn assert_cycle(g: &Graph, cycle: &[usize]) {
+        assert!(cycle.len() >= 2);
+        assert_eq!(cycle.first(), cycle.last());
+
+        let mut inner = cycle[1..].to_vec();
+        inner.sort_unstable();
+        inner.dedup();
+        assert_eq!(inner.len(), cycle.len() - 1);
+
+        cycle
+            .windows(2)
+            .for_each(|e| assert!(g.adj(e[0]).any(|&w| w == e[1])));
+    }
+
+    #[test]
+    fn special_edges() {
+        let g = read_graph("3 3  0 1  1 2  2 2".as_bytes()).unwrap();
+        assert_eq!(Cycle::new(&g).cycle(), Some(&[2, 2][..]));
+
+        let g = read_graph("3 3  0 1  1 2  2 1".as_bytes()).unwrap();
+        let cycle = Cycle::new(&g);
+        assert_eq!(cycle.cycle().unwrap().len(), 3);
+        assert_cycle(&g, cycle.cycle().unwrap());
+
+        let g = read_graph("4 3  0 1  1 2  1 3".as_bytes()).unwrap();
+        assert!(!Cycle::new(&g).has_cycle());
+    }
+
+    #[test]
+    fn tiny_g() {
+        let g = read_graph(TINY_G.as_bytes()).unwrap();
+
+        let cycle = Cycle::new(&g);
+        assert_cycle(&g, cycle.cycle().unwrap());
+    }
+
+    #[test]
+    fn random_forests() {
+        let mut rng = thread_rng();
+
+        (0..100).for_each(|_| {
+            let n = rng.gen_range(2..30);
+            let mut g = Graph::new(n);
+            let mut uf = WeightedQuickUnion::new(n);
+
+            // a forest until the first edge inside a component
+            let closing = loop {
+                let (v, w) = (rng.gen_range(0..n), rng.gen_range(0..n));
+                if uf.connected(v, w) {
+                    break (v, w);
+                }
+
+                uf.union(v, w);
+                g.add_edge(v, w);
+                assert!(!Cycle::new(&g).has_cycle());
+
+                if uf.count() == 1 {
+                    break (rng.gen_range(0..n), rng.gen_range(0..n));
+                }
+            };
+
+            g.add_edge(closing.0, closing.1);
+            let cycle = Cycle::new(&g);
+            assert_cycle(&g, cycle.cycle().unwrap());
+        });
+    }
+}
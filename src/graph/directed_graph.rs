@@ -1,10 +1,22 @@
 use core::num;
-use std::{collections::VecDeque, fmt::Debug, path::Path};
-
-use super::parse_graph_params;
+use std::{
+    fmt::Debug,
+    fs::File,
+    io::{BufReader, Read},
+    ops::ControlFlow,
+    path::Path,
+};
+
+use crate::collections::Bag;
+use crate::input::{read_edges, ParseError};
+
+use super::{
+    search::{depth_first, DfsEvent},
+    GraphLike,
+};
 
 pub struct Digraph {
-    adj_list: Vec<Vec<usize>>,
+    adj_list: Vec<Bag<usize>>,
     num_edges: usize,
 }
 
@@ -15,7 +27,7 @@ impl Digraph {
 
     pub fn new(num_vertices: usize) -> Self {
         Digraph {
-            adj_list: vec![vec![]; num_vertices],
+            adj_list: (0..num_vertices).map(|_| Bag::new()).collect(),
             num_edges: 0,
         }
     }
@@ -29,13 +41,13 @@ impl Digraph {
     }
 
     pub fn add_edge(&mut self, v: usize, w: usize) {
-        self.adj_list[v].push(w);
+        self.adj_list[v].add(w);
         self.num_edges += 1;
     }
 
-    /// rev because the textbook use linked-list stack
+    /// most recently added first, as with the textbook's linked-list bag
     pub fn adj(&self, v: usize) -> impl Iterator<Item = &usize> {
-        self.adj_list[v].iter().rev()
+        self.adj_list[v].iter()
     }
 
     pub fn reverse(&self) -> Self {
@@ -51,6 +63,20 @@ impl Digraph {
     }
 }
 
+impl GraphLike for Digraph {
+    fn num_vertices(&self) -> usize {
+        self.num_vertices()
+    }
+
+    fn num_edges(&self) -> usize {
+        self.num_edges()
+    }
+
+    fn adj(&self, v: usize) -> impl Iterator<Item = &usize> {
+        self.adj(v)
+    }
+}
+
 impl Debug for Digraph {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         writeln!(
@@ -74,181 +100,50 @@ impl Debug for Digraph {
     }
 }
 
-struct DirectedDFS {
-    marked: Vec<bool>,
-}
-
-impl DirectedDFS {
-    fn search(&mut self, g: &Digraph, v: usize) {
-        self.marked[v] = true;
-        g.adj(v).for_each(|&w| {
-            if !self.marked(w) {
-                self.search(g, w);
-            }
-        });
-    }
-
-    fn new(g: &Digraph, s: usize) -> Self {
-        let num_vertices = g.num_vertices();
-
-        let mut dfs = DirectedDFS {
-            marked: vec![false; num_vertices],
-        };
-
-        dfs.search(&g, s);
-
-        dfs
-    }
-
-    fn marked(&self, v: usize) -> bool {
-        self.marked[v]
-    }
-}
-
-#[derive(Debug)]
-struct DirectedDFP {
-    marked: Vec<bool>,
-    edge_to: Vec<Option<usize>>,
-}
-
-impl DirectedDFP {
-    fn search(&mut self, g: &Digraph, v: usize) {
-        self.marked[v] = true;
-
-        g.adj(v).for_each(|&w| {
-            if !self.marked[w] {
-                self.edge_to[w] = Some(v);
-                self.search(g, w);
-            }
-        });
-    }
-
-    fn new(g: &Digraph, s: usize) -> Self {
-        let num_vertices = g.num_vertices();
-
-        let mut dfp = DirectedDFP {
-            marked: vec![false; num_vertices],
-            edge_to: vec![None; num_vertices],
-        };
-
-        dfp.search(g, s);
-
-        dfp
-    }
-
-    fn path_to(&self, mut v: usize) -> Option<Vec<usize>> {
-        if !self.marked[v] {
-            return None;
-        }
-
-        let mut path = vec![];
-
-        path.push(v);
-
-        while let Some(from) = self.edge_to[v] {
-            path.push(from);
-            v = from;
-        }
-
-        path.reverse();
-
-        Some(path)
-    }
-}
-
 #[derive(Debug)]
-struct DirectedBFP {
+pub struct DirectedCycle {
     marked: Vec<bool>,
     edge_to: Vec<Option<usize>>,
+    on_stack: Vec<bool>,
+    pub cycle: Option<Vec<usize>>,
 }
 
-impl DirectedBFP {
+impl DirectedCycle {
     fn search(&mut self, g: &Digraph, v: usize) {
-        let mut unvisited = VecDeque::new();
-
-        unvisited.push_back(v);
-
-        while let Some(v) = unvisited.pop_front() {
-            g.adj(v).for_each(|&w| {
-                if !self.marked[w] {
-                    self.marked[w] = true;
-                    self.edge_to[w] = Some(v);
-                    unvisited.push_back(w);
-                }
-            });
-        }
-    }
-
-    fn new(g: &Digraph, s: usize) -> Self {
-        let num_vertices = g.num_vertices();
-
-        let mut bfp = DirectedBFP {
-            marked: vec![false; num_vertices],
-            edge_to: vec![None; num_vertices],
-        };
-
-        bfp.search(g, s);
-
-        bfp
-    }
+        let DirectedCycle {
+            edge_to,
+            on_stack,
+            cycle,
+            ..
+        } = self;
 
-    fn path_to(&self, mut v: usize) -> Option<Vec<usize>> {
-        if !self.marked[v] {
-            return None;
-        }
+        depth_first(g, v, &mut self.marked, |event| {
+            match event {
+                DfsEvent::Discover(v) => on_stack[v] = true,
+                DfsEvent::TreeEdge(v, w) => edge_to[w] = Some(v),
+                DfsEvent::NonTreeEdge(v, w) if on_stack[w] => {
+                    let mut found = vec![];
 
-        let mut path = vec![];
+                    let mut x = v;
 
-        path.push(v);
+                    while x != w {
+                        found.push(x);
+                        x = edge_to[x].unwrap();
+                    }
 
-        while let Some(from) = self.edge_to[v] {
-            path.push(from);
-            v = from;
-        }
+                    found.push(w);
+                    found.push(v);
 
-        path.reverse();
+                    *cycle = Some(found);
 
-        Some(path)
-    }
-}
-
-#[derive(Debug)]
-pub struct DirectedCycle {
-    marked: Vec<bool>,
-    edge_to: Vec<Option<usize>>,
-    on_stack: Vec<bool>,
-    pub cycle: Option<Vec<usize>>,
-}
-
-impl DirectedCycle {
-    fn search(&mut self, g: &Digraph, v: usize) {
-        self.marked[v] = true;
-        self.on_stack[v] = true;
-
-        g.adj(v).for_each(|&w| {
-            if self.cycle.is_some() {
-                return;
-            } else if !self.marked[w] {
-                self.edge_to[w] = Some(v);
-                self.search(g, w);
-            } else if self.on_stack[w] {
-                let mut cycle = vec![];
-
-                let mut x = v;
-
-                while x != w {
-                    cycle.push(x);
-                    x = self.edge_to[x].unwrap();
+                    return ControlFlow::Break(());
                 }
-
-                cycle.push(w);
-                cycle.push(v);
-
-                self.cycle = Some(cycle);
+                DfsEvent::NonTreeEdge(..) => {}
+                DfsEvent::Finish(v) => on_stack[v] = false,
             }
-        });
 
-        self.on_stack[v] = false;
+            ControlFlow::Continue(())
+        });
     }
 
     pub fn new(g: &Digraph) -> Self {
@@ -262,7 +157,7 @@ impl DirectedCycle {
         };
 
         g.vertices().for_each(|v| {
-            if !dc.marked[v] {
+            if !dc.marked[v] && dc.cycle.is_none() {
                 dc.search(g, v);
             }
         });
@@ -271,8 +166,9 @@ impl DirectedCycle {
     }
 }
 
-pub fn create_digraph_from_path<P: AsRef<Path>>(path: P) -> Digraph {
-    let (num_vertices, edges) = parse_graph_params(path);
+/// Read a digraph in the algs4-data format from any reader.
+pub fn read_digraph<R: Read>(reader: R) -> Result<Digraph, ParseError> {
+    let (num_vertices, edges) = read_edges(reader)?;
 
     let mut g = Digraph::new(num_vertices);
 
@@ -280,12 +176,21 @@ pub fn create_digraph_from_path<P: AsRef<Path>>(path: P) -> Digraph {
         g.add_edge(a, b);
     });
 
-    g
+    Ok(g)
+}
+
+pub fn create_digraph_from_path<P: AsRef<Path>>(path: P) -> Digraph {
+    let file = File::open(path).unwrap();
+
+    read_digraph(BufReader::new(file)).unwrap_or_else(|e| panic!("{}", e))
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::graph::{print_path, undirected_graph::DepthFirstSearch};
+    use crate::graph::{
+        print_path,
+        search::{BreadthFirstPaths, DepthFirstPaths, DepthFirstSearch, Paths, Search},
+    };
 
     use super::*;
 
@@ -300,7 +205,7 @@ mod tests {
     fn dfs() {
         let mut g = create_digraph_from_path("./algs4-data/tinyDG.txt");
 
-        let dfs = DirectedDFS::new(&g, 6);
+        let dfs = DepthFirstSearch::new(&g, 6);
 
         (0..g.num_vertices())
             .filter(|&v| dfs.marked(v))
@@ -313,7 +218,7 @@ mod tests {
     fn path() {
         let mut g = create_digraph_from_path("./algs4-data/tinyDG.txt");
 
-        let dfp = DirectedDFP::new(&g, 7);
+        let dfp = DepthFirstPaths::new(&g, 7);
 
         (0..g.num_vertices()).for_each(|v| {
             if let Some(path) = dfp.path_to(v) {
@@ -323,7 +228,7 @@ mod tests {
 
         println!();
 
-        let bfp = DirectedBFP::new(&g, 7);
+        let bfp = BreadthFirstPaths::new(&g, 7);
 
         (0..g.num_vertices()).for_each(|v| {
             if let Some(path) = bfp.path_to(v) {
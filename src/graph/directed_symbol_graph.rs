@@ -6,7 +6,7 @@ use std::{
     path::Path,
 };
 
-use super::directed_graph::Digraph;
+use super::{directed_graph::Digraph, GraphLike};
 
 #[derive(Debug)]
 pub struct SymbolDigraph {
@@ -65,6 +65,20 @@ impl SymbolDigraph {
     }
 }
 
+impl GraphLike for SymbolDigraph {
+    fn num_vertices(&self) -> usize {
+        self.g.num_vertices()
+    }
+
+    fn num_edges(&self) -> usize {
+        self.g.num_edges()
+    }
+
+    fn adj(&self, v: usize) -> impl Iterator<Item = &usize> {
+        self.g.adj(v)
+    }
+}
+
 pub fn create_symbol_digraph<T: AsRef<Path>>(path: T, delim: &str) -> SymbolDigraph {
     let file = File::open(path).unwrap();
 
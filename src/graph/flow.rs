@@ -0,0 +1,571 @@
+use std::{
+    fs::File,
+    io::{BufReader, Read},
+    path::Path,
+};
+
+use crate::{
+    collections::Queue,
+    input::{read_capacities, ParseError},
+};
+
+use super::{
+    search::{BreadthFirstPaths, DepthFirstSearch, Paths, Search},
+    GraphLike,
+};
+
+/// Edge from -> to of a flow network, carrying `flow` out of `capacity`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FlowEdge {
+    from: usize,
+    to: usize,
+    capacity: f64,
+    flow: f64,
+}
+
+impl FlowEdge {
+    pub fn new(from: usize, to: usize, capacity: f64) -> Self {
+        assert!(
+            capacity.is_finite() && capacity >= 0.0,
+            "capacity must be finite and non-negative"
+        );
+
+        FlowEdge {
+            from,
+            to,
+            capacity,
+            flow: 0.0,
+        }
+    }
+
+    pub fn from(&self) -> usize {
+        self.from
+    }
+
+    pub fn to(&self) -> usize {
+        self.to
+    }
+
+    pub fn capacity(&self) -> f64 {
+        self.capacity
+    }
+
+    pub fn flow(&self) -> f64 {
+        self.flow
+    }
+
+    /// endpoint that isn't v
+    pub fn other(&self, v: usize) -> usize {
+        *self.other_ref(v)
+    }
+
+    fn other_ref(&self, v: usize) -> &usize {
+        if v == self.from {
+            &self.to
+        } else if v == self.to {
+            &self.from
+        } else {
+            panic!("{} is not an endpoint of {}->{}", v, self.from, self.to)
+        }
+    }
+
+    /// how much more can be pushed towards v: the remaining capacity
+    /// forwards, the flow itself backwards
+    pub fn residual_capacity_to(&self, v: usize) -> f64 {
+        if v == self.to {
+            self.capacity - self.flow
+        } else if v == self.from {
+            self.flow
+        } else {
+            panic!("{} is not an endpoint of {}->{}", v, self.from, self.to)
+        }
+    }
+
+    pub fn add_residual_flow_to(&mut self, v: usize, delta: f64) {
+        if v == self.to {
+            self.flow += delta;
+        } else if v == self.from {
+            self.flow -= delta;
+        } else {
+            panic!("{} is not an endpoint of {}->{}", v, self.from, self.to)
+        }
+    }
+}
+
+/// Edge weighted digraph where every edge is listed in the adjacency lists of
+/// both its endpoints, so the residual network can be walked either way.
+#[derive(Debug, Clone)]
+pub struct FlowNetwork {
+    edges: Vec<FlowEdge>,
+    /// indices into edges
+    adj: Vec<Vec<usize>>,
+}
+
+impl FlowNetwork {
+    pub fn new(num_vertices: usize) -> Self {
+        FlowNetwork {
+            edges: Vec::new(),
+            adj: vec![Vec::new(); num_vertices],
+        }
+    }
+
+    pub fn num_vertices(&self) -> usize {
+        self.adj.len()
+    }
+
+    pub fn num_edges(&self) -> usize {
+        self.edges.len()
+    }
+
+    /// adds e and returns its index
+    pub fn add_edge(&mut self, e: FlowEdge) -> usize {
+        let index = self.edges.len();
+
+        self.adj[e.from].push(index);
+        if e.to != e.from {
+            self.adj[e.to].push(index);
+        }
+        self.edges.push(e);
+
+        index
+    }
+
+    pub fn edge(&self, index: usize) -> &FlowEdge {
+        &self.edges[index]
+    }
+
+    /// edges in insertion order
+    pub fn edges(&self) -> impl Iterator<Item = &FlowEdge> {
+        self.edges.iter()
+    }
+
+    /// edges pointing from or to v
+    pub fn adj(&self, v: usize) -> impl Iterator<Item = &FlowEdge> {
+        self.adj[v].iter().map(|&e| &self.edges[e])
+    }
+
+    /// flow into v minus flow out of v
+    pub fn excess(&self, v: usize) -> f64 {
+        self.adj(v)
+            .filter(|e| e.from != e.to)
+            .map(|e| if e.to == v { e.flow } else { -e.flow })
+            .sum()
+    }
+
+    pub fn clear_flow(&mut self) {
+        self.edges.iter_mut().for_each(|e| e.flow = 0.0);
+    }
+
+    /// vertices reachable from s through edges with residual capacity left
+    fn residual_reachable(&self, s: usize) -> Vec<bool> {
+        let dfs = DepthFirstSearch::new(&Residual(self), s);
+
+        (0..self.num_vertices()).map(|v| dfs.marked(v)).collect()
+    }
+}
+
+/// The residual network of a flow network: v -> w whenever some edge between
+/// them can still push flow towards w.
+struct Residual<'a>(&'a FlowNetwork);
+
+impl GraphLike for Residual<'_> {
+    fn num_vertices(&self) -> usize {
+        self.0.num_vertices()
+    }
+
+    fn num_edges(&self) -> usize {
+        self.0.num_edges()
+    }
+
+    fn adj(&self, v: usize) -> impl Iterator<Item = &usize> {
+        self.0
+            .adj(v)
+            .filter(move |e| e.residual_capacity_to(e.other(v)) > 0.0)
+            .map(move |e| e.other_ref(v))
+    }
+}
+
+pub fn read_flow_network<R: Read>(reader: R) -> Result<FlowNetwork, ParseError> {
+    let (num_vertices, edges) = read_capacities(reader)?;
+
+    let mut g = FlowNetwork::new(num_vertices);
+
+    edges.into_iter().for_each(|(v, w, capacity)| {
+        g.add_edge(FlowEdge::new(v, w, capacity));
+    });
+
+    Ok(g)
+}
+
+pub fn create_flow_network_from_path<P: AsRef<Path>>(path: P) -> FlowNetwork {
+    let file = File::open(path).unwrap();
+
+    read_flow_network(BufReader::new(file)).unwrap_or_else(|e| panic!("{}", e))
+}
+
+/// Minimum s-t cut: the source side, the edges leaving it, and their total
+/// capacity, which equals the maximum flow value.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MinCut {
+    pub source_side: Vec<usize>,
+    pub edges: Vec<FlowEdge>,
+    pub capacity: f64,
+}
+
+pub trait MaxFlow {
+    /// maximum flow from s to t, left in the edges of g; any flow g already
+    /// carries is discarded first
+    fn new(g: &mut FlowNetwork, s: usize, t: usize) -> Self;
+
+    fn value(&self) -> f64;
+
+    /// is v on the source side of the minimum cut?
+    fn in_cut(&self, v: usize) -> bool;
+
+    fn min_cut(&self, g: &FlowNetwork) -> MinCut {
+        let source_side: Vec<usize> = (0..g.num_vertices()).filter(|&v| self.in_cut(v)).collect();
+
+        let edges: Vec<FlowEdge> = g
+            .edges()
+            .filter(|e| self.in_cut(e.from) && !self.in_cut(e.to))
+            .copied()
+            .collect();
+
+        let capacity = edges.iter().map(|e| e.capacity).sum();
+
+        MinCut {
+            source_side,
+            edges,
+            capacity,
+        }
+    }
+}
+
+fn check_endpoints(g: &FlowNetwork, s: usize, t: usize) {
+    assert!(
+        s < g.num_vertices() && t < g.num_vertices(),
+        "vertex out of range"
+    );
+    assert_ne!(s, t, "source and sink must differ");
+}
+
+/// Ford-Fulkerson with shortest augmenting paths (Edmonds-Karp): each path
+/// is found by breadth-first search in the residual network.
+#[derive(Debug)]
+pub struct FordFulkerson {
+    value: f64,
+    marked: Vec<bool>,
+}
+
+impl MaxFlow for FordFulkerson {
+    fn new(g: &mut FlowNetwork, s: usize, t: usize) -> Self {
+        check_endpoints(g, s, t);
+        g.clear_flow();
+
+        let mut value = 0.0;
+
+        while let Some(path) = BreadthFirstPaths::new(&Residual(g), s).path_to(t) {
+            // the widest of any parallel edges for every hop
+            let hops: Vec<(usize, usize)> = path
+                .windows(2)
+                .map(|hop| {
+                    let (v, w) = (hop[0], hop[1]);
+                    let e = g.adj[v]
+                        .iter()
+                        .copied()
+                        .filter(|&e| g.edges[e].other(v) == w)
+                        .max_by(|&a, &b| {
+                            g.edges[a]
+                                .residual_capacity_to(w)
+                                .total_cmp(&g.edges[b].residual_capacity_to(w))
+                        })
+                        .unwrap();
+
+                    (e, w)
+                })
+                .collect();
+
+            let bottleneck = hops
+                .iter()
+                .map(|&(e, w)| g.edges[e].residual_capacity_to(w))
+                .fold(f64::INFINITY, f64::min);
+
+            hops.iter()
+                .for_each(|&(e, w)| g.edges[e].add_residual_flow_to(w, bottleneck));
+
+            value += bottleneck;
+        }
+
+        FordFulkerson {
+            value,
+            marked: g.residual_reachable(s),
+        }
+    }
+
+    fn value(&self) -> f64 {
+        self.value
+    }
+
+    fn in_cut(&self, v: usize) -> bool {
+        self.marked[v]
+    }
+}
+
+/// Dinic's algorithm: breadth-first search layers the residual network by
+/// distance from s, then a blocking flow is pushed along the layered edges
+/// with a current-edge pointer per vertex so no edge is retried in vain.
+/// O(V^2 E) against O(V E^2) for shortest augmenting paths.
+#[derive(Debug)]
+pub struct Dinic {
+    value: f64,
+    marked: Vec<bool>,
+}
+
+impl Dinic {
+    /// distance from s in the residual network, None if unreachable
+    fn levels(g: &FlowNetwork, s: usize) -> Vec<Option<usize>> {
+        let mut level = vec![None; g.num_vertices()];
+        level[s] = Some(0);
+
+        let mut queue = Queue::new();
+        queue.enqueue(s);
+
+        while let Some(v) = queue.dequeue() {
+            let next = level[v].map(|l| l + 1);
+
+            Residual(g).adj(v).for_each(|&w| {
+                if level[w].is_none() {
+                    level[w] = next;
+                    queue.enqueue(w);
+                }
+            });
+        }
+
+        level
+    }
+
+    /// saturates every s-t path of the layered network, iteratively
+    fn blocking_flow(g: &mut FlowNetwork, s: usize, t: usize, level: &mut [Option<usize>]) -> f64 {
+        let mut current = vec![0; g.num_vertices()];
+        let mut path: Vec<usize> = Vec::new();
+        let mut total = 0.0;
+        let mut v = s;
+
+        loop {
+            if v == t {
+                let mut bottleneck = f64::INFINITY;
+                let mut w = s;
+                path.iter().for_each(|&e| {
+                    w = g.edges[e].other(w);
+                    bottleneck = bottleneck.min(g.edges[e].residual_capacity_to(w));
+                });
+
+                let mut w = s;
+                path.iter().for_each(|&e| {
+                    w = g.edges[e].other(w);
+                    g.edges[e].add_residual_flow_to(w, bottleneck);
+                });
+
+                total += bottleneck;
+                path.clear();
+                v = s;
+                continue;
+            }
+
+            let next = level[v].map(|l| l + 1);
+            let mut advanced = false;
+
+            while current[v] < g.adj[v].len() {
+                let e = g.adj[v][current[v]];
+                let w = g.edges[e].other(v);
+
+                if level[w] == next && g.edges[e].residual_capacity_to(w) > 0.0 {
+                    path.push(e);
+                    v = w;
+                    advanced = true;
+                    break;
+                }
+
+                current[v] += 1;
+            }
+
+            if advanced {
+                continue;
+            }
+
+            // dead end: nothing more goes through v in this phase
+            level[v] = None;
+
+            match path.pop() {
+                Some(e) => {
+                    v = g.edges[e].other(v);
+                    current[v] += 1;
+                }
+                None => return total,
+            }
+        }
+    }
+}
+
+impl MaxFlow for Dinic {
+    fn new(g: &mut FlowNetwork, s: usize, t: usize) -> Self {
+        check_endpoints(g, s, t);
+        g.clear_flow();
+
+        let mut value = 0.0;
+
+        loop {
+            let mut level = Dinic::levels(g, s);
+            if level[t].is_none() {
+                break;
+            }
+
+            value += Dinic::blocking_flow(g, s, t, &mut level);
+        }
+
+        Dinic {
+            value,
+            marked: g.residual_reachable(s),
+        }
+    }
+
+    fn value(&self) -> f64 {
+        self.value
+    }
+
+    fn in_cut(&self, v: usize) -> bool {
+        self.marked[v]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{thread_rng, Rng};
+
+    use super::*;
+
+    /// tinyFN.txt
+    const TINY_FN: &str =
+        "6 8  0 1 2.0  0 2 3.0  1 3 3.0  1 4 1.0  2 3 1.0  2 4 1.0  3 5 2.0  4 5 3.0";
+
+    fn random_network(num_vertices: usize, num_edges: usize) -> FlowNetwork {
+        let mut rng = thread_rng();
+        let mut g = FlowNetwork::new(num_vertices);
+
+        (0..num_edges).for_each(|_| {
+            let capacity = rng.gen_range(0..10) as f64;
+            g.add_edge(FlowEdge::new(
+                rng.gen_range(0..num_vertices),
+                rng.gen_range(0..num_vertices),
+                capacity,
+            ));
+        });
+
+        g
+    }
+
+    /// capacity and conservation constraints, and max-flow = min-cut
+    fn check<F: MaxFlow>(g: &FlowNetwork, flow: &F, s: usize, t: usize) {
+        assert!(g.edges().all(|e| 0.0 <= e.flow && e.flow <= e.capacity));
+
+        (0..g.num_vertices())
+            .filter(|&v| v != s && v != t)
+            .for_each(|v| assert_eq!(g.excess(v), 0.0));
+        assert_eq!(g.excess(t), flow.value());
+        assert_eq!(g.excess(s), -flow.value());
+
+        assert!(flow.in_cut(s));
+        assert!(!flow.in_cut(t));
+
+        let cut = flow.min_cut(g);
+        assert_eq!(cut.capacity, flow.value());
+        assert!(cut.edges.iter().all(|e| e.flow == e.capacity));
+    }
+
+    #[test]
+    fn tiny_fn() {
+        let mut g = read_flow_network(TINY_FN.as_bytes()).unwrap();
+        assert_eq!(g.num_vertices(), 6);
+        assert_eq!(g.num_edges(), 8);
+        assert_eq!(g.adj(1).count(), 3);
+
+        let ff = FordFulkerson::new(&mut g, 0, 5);
+        assert_eq!(ff.value(), 4.0);
+        check(&g, &ff, 0, 5);
+
+        let cut = ff.min_cut(&g);
+        assert_eq!(cut.source_side, [0, 2]);
+        assert_eq!(
+            cut.edges
+                .iter()
+                .map(|e| (e.from(), e.to()))
+                .collect::<Vec<_>>(),
+            [(0, 1), (2, 3), (2, 4)]
+        );
+
+        let dinic = Dinic::new(&mut g, 0, 5);
+        assert_eq!(dinic.value(), 4.0);
+        check(&g, &dinic, 0, 5);
+        assert_eq!(dinic.min_cut(&g), cut);
+    }
+
+    #[test]
+    fn residual_edges() {
+        let mut e = FlowEdge::new(1, 2, 3.0);
+        assert_eq!(e.other(1), 2);
+        assert_eq!(e.residual_capacity_to(2), 3.0);
+        assert_eq!(e.residual_capacity_to(1), 0.0);
+
+        e.add_residual_flow_to(2, 2.0);
+        assert_eq!(e.flow(), 2.0);
+        assert_eq!(e.residual_capacity_to(2), 1.0);
+        assert_eq!(e.residual_capacity_to(1), 2.0);
+
+        e.add_residual_flow_to(1, 0.5);
+        assert_eq!(e.flow(), 1.5);
+    }
+
+    #[test]
+    fn disconnected_and_parallel() {
+        let mut g =
+            read_flow_network("4 4  0 1 1.0  0 1 2.0  1 0 5.0  2 3 7.0".as_bytes()).unwrap();
+
+        let ff = FordFulkerson::new(&mut g, 0, 1);
+        assert_eq!(ff.value(), 3.0);
+        check(&g, &ff, 0, 1);
+
+        let dinic = Dinic::new(&mut g, 0, 3);
+        assert_eq!(dinic.value(), 0.0);
+        assert_eq!(dinic.min_cut(&g).source_side, [0, 1]);
+        assert!(dinic.min_cut(&g).edges.is_empty());
+    }
+
+    #[test]
+    fn random_networks() {
+        let mut rng = thread_rng();
+
+        (0..200).for_each(|_| {
+            let num_vertices = rng.gen_range(2..20);
+            let mut g = random_network(num_vertices, rng.gen_range(0..60));
+            let s = rng.gen_range(0..num_vertices);
+            let t = (s + rng.gen_range(1..num_vertices)) % num_vertices;
+
+            let ff = FordFulkerson::new(&mut g, s, t);
+            check(&g, &ff, s, t);
+
+            let dinic = Dinic::new(&mut g, s, t);
+            check(&g, &dinic, s, t);
+
+            assert_eq!(ff.value(), dinic.value());
+            assert_eq!(ff.min_cut(&g), dinic.min_cut(&g));
+        });
+    }
+
+    #[test]
+    fn large() {
+        let mut g = random_network(2000, 20000);
+
+        let value = Dinic::new(&mut g, 0, 1).value();
+        assert_eq!(FordFulkerson::new(&mut g, 0, 1).value(), value);
+    }
+}
@@ -0,0 +1,258 @@
+use super::{directed_graph::Digraph, topological::Cycle};
+
+/// A topological order kept up to date while edges are inserted, after
+/// Pearce and Kelly. Inserting v -> w when v already comes before w costs
+/// nothing; otherwise only the vertices positioned between w and v that
+/// are reachable forward from w or backward from v are searched, and they
+/// are reordered among their own positions.
+#[derive(Debug)]
+pub struct IncrementalTopological {
+    g: Digraph,
+    /// incoming edges, for the backward search
+    reverse: Vec<Vec<usize>>,
+    /// position of every vertex
+    position: Vec<usize>,
+    /// vertex at every position
+    order: Vec<usize>,
+    /// search state, false and None again between insertions so that a
+    /// search only pays for the vertices it visits
+    marked: Vec<bool>,
+    edge_to: Vec<Option<usize>>,
+}
+
+impl IncrementalTopological {
+    /// No edges yet, so the vertices in increasing order.
+    pub fn new(num_vertices: usize) -> Self {
+        IncrementalTopological {
+            g: Digraph::new(num_vertices),
+            reverse: vec![Vec::new(); num_vertices],
+            position: (0..num_vertices).collect(),
+            order: (0..num_vertices).collect(),
+            marked: vec![false; num_vertices],
+            edge_to: vec![None; num_vertices],
+        }
+    }
+
+    pub fn order(&self) -> &[usize] {
+        &self.order
+    }
+
+    pub fn position(&self, v: usize) -> usize {
+        self.position[v]
+    }
+
+    /// the edges inserted so far
+    pub fn digraph(&self) -> &Digraph {
+        &self.g
+    }
+
+    /// Insert v -> w, or leave everything unchanged and return the cycle
+    /// the edge would close.
+    pub fn add_edge(&mut self, v: usize, w: usize) -> Result<(), Cycle> {
+        let lower = self.position[w];
+        let upper = self.position[v];
+
+        if lower < upper {
+            let forward = self.forward(w, v, upper)?;
+            let backward = self.backward(v, lower);
+            self.reorder(backward, forward);
+        } else if v == w {
+            return Err(Cycle {
+                vertices: vec![v, v],
+            });
+        }
+
+        self.g.add_edge(v, w);
+        self.reverse[w].push(v);
+
+        Ok(())
+    }
+
+    /// Vertices reachable from w without passing position `upper`, or the
+    /// cycle if v is one of them.
+    fn forward(&mut self, w: usize, v: usize, upper: usize) -> Result<Vec<usize>, Cycle> {
+        let mut visited = vec![w];
+        let result = self.search_forward(w, v, upper, &mut visited);
+
+        visited.iter().for_each(|&x| {
+            self.marked[x] = false;
+            self.edge_to[x] = None;
+        });
+
+        result.map(|_| visited)
+    }
+
+    fn search_forward(
+        &mut self,
+        w: usize,
+        v: usize,
+        upper: usize,
+        visited: &mut Vec<usize>,
+    ) -> Result<(), Cycle> {
+        let IncrementalTopological {
+            g,
+            position,
+            marked,
+            edge_to,
+            ..
+        } = self;
+        let mut stack = vec![w];
+        marked[w] = true;
+
+        while let Some(x) = stack.pop() {
+            for &y in g.adj(x) {
+                if y == v {
+                    // w -> ... -> x -> v, closed by the new edge v -> w
+                    let mut path = vec![x];
+                    let mut z = x;
+                    while let Some(from) = edge_to[z] {
+                        path.push(from);
+                        z = from;
+                    }
+                    path.reverse();
+
+                    let vertices = std::iter::once(v)
+                        .chain(path)
+                        .chain(std::iter::once(v))
+                        .collect();
+
+                    return Err(Cycle { vertices });
+                }
+
+                if !marked[y] && position[y] < upper {
+                    marked[y] = true;
+                    edge_to[y] = Some(x);
+                    visited.push(y);
+                    stack.push(y);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Vertices that reach v without going below position `lower`.
+    fn backward(&mut self, v: usize, lower: usize) -> Vec<usize> {
+        let IncrementalTopological {
+            reverse,
+            position,
+            marked,
+            ..
+        } = self;
+        let mut visited = vec![v];
+        let mut stack = vec![v];
+        marked[v] = true;
+
+        while let Some(x) = stack.pop() {
+            reverse[x].iter().for_each(|&y| {
+                if !marked[y] && position[y] > lower {
+                    marked[y] = true;
+                    visited.push(y);
+                    stack.push(y);
+                }
+            });
+        }
+
+        visited.iter().for_each(|&x| marked[x] = false);
+
+        visited
+    }
+
+    /// Give the positions held by both sets to the backward set first, each
+    /// set keeping its relative order.
+    fn reorder(&mut self, mut backward: Vec<usize>, mut forward: Vec<usize>) {
+        backward.sort_unstable_by_key(|&x| self.position[x]);
+        forward.sort_unstable_by_key(|&x| self.position[x]);
+
+        let mut positions: Vec<usize> = backward
+            .iter()
+            .chain(&forward)
+            .map(|&x| self.position[x])
+            .collect();
+        positions.sort_unstable();
+
+        backward
+            .into_iter()
+            .chain(forward)
+            .zip(positions)
+            .for_each(|(x, p)| {
+                self.position[x] = p;
+                self.order[p] = x;
+            });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{thread_rng, Rng};
+
+    use crate::graph::search::{DepthFirstSearch, Search};
+
+    use super::*;
+
+    fn assert_order(topological: &IncrementalTopological) {
+        let g = topological.digraph();
+
+        // every search cleans up after itself
+        assert!(topological.marked.iter().all(|&marked| !marked));
+        assert!(topological.edge_to.iter().all(Option::is_none));
+
+        g.vertices().for_each(|v| {
+            assert_eq!(topological.order()[topological.position(v)], v);
+            g.adj(v)
+                .for_each(|&w| assert!(topological.position(v) < topological.position(w)));
+        });
+    }
+
+    #[test]
+    fn reorders() {
+        let mut topological = IncrementalTopological::new(4);
+
+        topological.add_edge(0, 1).unwrap();
+        assert_eq!(topological.order(), [0, 1, 2, 3]);
+
+        // only 0 and 1 have to move past 3, 2 keeps its place
+        topological.add_edge(3, 0).unwrap();
+        assert_eq!(topological.order(), [3, 0, 2, 1]);
+
+        topological.add_edge(2, 3).unwrap();
+        assert_eq!(topological.order(), [2, 3, 0, 1]);
+        assert_order(&topological);
+
+        let cycle = topological.add_edge(1, 2).unwrap_err();
+        assert_eq!(cycle.vertices, [1, 2, 3, 0, 1]);
+        assert_eq!(topological.digraph().num_edges(), 3);
+
+        assert_eq!(topological.add_edge(1, 1).unwrap_err().vertices, [1, 1]);
+    }
+
+    #[test]
+    fn random() {
+        let mut rng = thread_rng();
+
+        (0..50).for_each(|_| {
+            let n = rng.gen_range(1..30);
+            let mut topological = IncrementalTopological::new(n);
+
+            (0..3 * n).for_each(|_| {
+                let (v, w) = (rng.gen_range(0..n), rng.gen_range(0..n));
+                let closes_cycle = DepthFirstSearch::new(topological.digraph(), w).marked(v);
+
+                match topological.add_edge(v, w) {
+                    Ok(()) => assert!(!closes_cycle),
+                    Err(cycle) => {
+                        assert!(closes_cycle);
+                        assert_eq!(cycle.vertices.first(), Some(&v));
+                        assert_eq!(cycle.vertices[1], w);
+                        assert_eq!(cycle.vertices.last(), Some(&v));
+                        cycle.vertices[1..].windows(2).for_each(|e| {
+                            assert!(topological.digraph().adj(e[0]).any(|&x| x == e[1]))
+                        });
+                    }
+                }
+
+                assert_order(&topological);
+            });
+        });
+    }
+}
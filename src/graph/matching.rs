@@ -0,0 +1,606 @@
+use std::{error::Error, fmt::Display};
+
+use crate::collections::Queue;
+
+use super::{bipartite::Bipartite, undirected_graph::Graph};
+
+/// Returned when asked to match a graph with an odd cycle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NotBipartite {
+    pub odd_cycle: Vec<usize>,
+}
+
+impl Display for NotBipartite {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "not bipartite, odd cycle {}",
+            self.odd_cycle
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<String>>()
+                .join(" - ")
+        )
+    }
+}
+
+impl Error for NotBipartite {}
+
+/// Maximum cardinality matching of a bipartite graph by Hopcroft-Karp, in
+/// O(E sqrt V): each phase layers the graph by breadth-first search from the
+/// free left vertices, then augments along a maximal set of vertex-disjoint
+/// shortest augmenting paths.
+///
+/// The left side is the color `false` of `Bipartite`. Alongside the matching
+/// comes a minimum vertex cover of the same size (König's theorem), which
+/// certifies that no larger matching exists.
+#[derive(Debug)]
+pub struct HopcroftKarp {
+    mate: Vec<Option<usize>>,
+    left: Vec<bool>,
+    size: usize,
+    in_cover: Vec<bool>,
+}
+
+impl HopcroftKarp {
+    pub fn new(g: &Graph) -> Result<Self, NotBipartite> {
+        let bipartite = Bipartite::new(g);
+        if let Some(cycle) = bipartite.odd_cycle() {
+            return Err(NotBipartite {
+                odd_cycle: cycle.to_vec(),
+            });
+        }
+
+        let num_vertices = g.num_vertices();
+        let left: Vec<bool> = (0..num_vertices).map(|v| !bipartite.color(v)).collect();
+        let adj: Vec<Vec<usize>> = (0..num_vertices)
+            .map(|v| g.adj(v).copied().collect())
+            .collect();
+
+        let mut matching = HopcroftKarp {
+            mate: vec![None; num_vertices],
+            left,
+            size: 0,
+            in_cover: vec![false; num_vertices],
+        };
+
+        while let Some(mut dist) = matching.layers(&adj) {
+            let mut current = vec![0; num_vertices];
+
+            let free: Vec<usize> = (0..num_vertices)
+                .filter(|&u| matching.left[u] && matching.mate[u].is_none())
+                .collect();
+
+            free.into_iter().for_each(|u| {
+                if matching.augment(&adj, u, &mut dist, &mut current) {
+                    matching.size += 1;
+                }
+            });
+        }
+
+        matching.in_cover = matching.konig_cover(&adj);
+
+        Ok(matching)
+    }
+
+    /// alternating distance of every left vertex from a free one, or None
+    /// once no augmenting path is left
+    fn layers(&self, adj: &[Vec<usize>]) -> Option<Vec<Option<usize>>> {
+        let mut dist = vec![None; self.mate.len()];
+        let mut queue = Queue::new();
+
+        (0..self.mate.len())
+            .filter(|&u| self.left[u] && self.mate[u].is_none())
+            .for_each(|u| {
+                dist[u] = Some(0);
+                queue.enqueue(u);
+            });
+
+        // length of the shortest augmenting paths, in left vertices
+        let mut shortest = None;
+
+        while let Some(u) = queue.dequeue() {
+            let d = dist[u].unwrap();
+            if shortest.is_some_and(|shortest| d > shortest) {
+                break;
+            }
+
+            adj[u].iter().for_each(|&w| match self.mate[w] {
+                None => shortest = shortest.or(Some(d)),
+                Some(x) if dist[x].is_none() => {
+                    dist[x] = Some(d + 1);
+                    queue.enqueue(x);
+                }
+                _ => {}
+            });
+        }
+
+        shortest.map(|_| dist)
+    }
+
+    /// depth-first search for an augmenting path from the free left vertex
+    /// s along the layers, with an explicit stack; dead ends leave the
+    /// layers so later searches of the phase skip them
+    fn augment(
+        &mut self,
+        adj: &[Vec<usize>],
+        s: usize,
+        dist: &mut [Option<usize>],
+        current: &mut [usize],
+    ) -> bool {
+        let mut stack = vec![s];
+
+        while let Some(&u) = stack.last() {
+            let Some(&w) = adj[u].get(current[u]) else {
+                dist[u] = None;
+                stack.pop();
+                continue;
+            };
+            current[u] += 1;
+
+            match self.mate[w] {
+                None => {
+                    // every left vertex on the stack takes the right vertex
+                    // it went through
+                    stack.iter().for_each(|&x| {
+                        let y = adj[x][current[x] - 1];
+                        self.mate[x] = Some(y);
+                        self.mate[y] = Some(x);
+                    });
+
+                    return true;
+                }
+                Some(x) if dist[x] == dist[u].map(|d| d + 1) => {
+                    stack.push(x);
+                }
+                _ => {}
+            }
+        }
+
+        false
+    }
+
+    /// König: with Z the vertices reachable from free left vertices by
+    /// alternating paths, (left - Z) + (right & Z) covers every edge
+    fn konig_cover(&self, adj: &[Vec<usize>]) -> Vec<bool> {
+        let num_vertices = self.mate.len();
+        let mut reached = vec![false; num_vertices];
+        let mut queue = Queue::new();
+
+        (0..num_vertices)
+            .filter(|&u| self.left[u] && self.mate[u].is_none())
+            .for_each(|u| {
+                reached[u] = true;
+                queue.enqueue(u);
+            });
+
+        while let Some(u) = queue.dequeue() {
+            adj[u].iter().for_each(|&w| {
+                if !reached[w] && self.mate[u] != Some(w) {
+                    reached[w] = true;
+
+                    // w is matched, or the matching wouldn't be maximum
+                    if let Some(x) = self.mate[w].filter(|&x| !reached[x]) {
+                        reached[x] = true;
+                        queue.enqueue(x);
+                    }
+                }
+            });
+        }
+
+        (0..num_vertices)
+            .map(|v| self.left[v] != reached[v])
+            .collect()
+    }
+
+    /// number of edges in the matching
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    pub fn mate(&self, v: usize) -> Option<usize> {
+        self.mate[v]
+    }
+
+    pub fn is_matched(&self, v: usize) -> bool {
+        self.mate[v].is_some()
+    }
+
+    /// does every vertex have a mate?
+    pub fn is_perfect(&self) -> bool {
+        2 * self.size == self.mate.len()
+    }
+
+    /// side of v, as chosen for the matching
+    pub fn is_left(&self, v: usize) -> bool {
+        self.left[v]
+    }
+
+    /// matched edges as (left, right), by left vertex
+    pub fn matching(&self) -> Vec<(usize, usize)> {
+        (0..self.mate.len())
+            .filter(|&v| self.left[v])
+            .filter_map(|v| self.mate[v].map(|w| (v, w)))
+            .collect()
+    }
+
+    pub fn in_min_vertex_cover(&self, v: usize) -> bool {
+        self.in_cover[v]
+    }
+
+    /// as many vertices as matched edges, touching every edge of the graph
+    pub fn min_vertex_cover(&self) -> Vec<usize> {
+        (0..self.mate.len()).filter(|&v| self.in_cover[v]).collect()
+    }
+}
+
+/// Minimum cost assignment of rows to distinct columns.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Assignment {
+    columns: Vec<Option<usize>>,
+    cost: f64,
+}
+
+impl Assignment {
+    /// column assigned to row, None only when there are more rows than
+    /// columns
+    pub fn column(&self, row: usize) -> Option<usize> {
+        self.columns[row]
+    }
+
+    /// (row, column) for every assigned row
+    pub fn pairs(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.columns
+            .iter()
+            .enumerate()
+            .filter_map(|(row, column)| column.map(|column| (row, column)))
+    }
+
+    /// total cost of the assigned pairs
+    pub fn cost(&self) -> f64 {
+        self.cost
+    }
+}
+
+/// Hungarian algorithm with potentials on a dense `rows x columns` cost
+/// matrix, O(n^2 m) for n = min(rows, columns) and m the other dimension.
+/// Every row gets a distinct column, or every column a distinct row if
+/// there are fewer of them, minimizing the total cost. Negate the costs to
+/// maximize instead.
+pub fn hungarian(cost: &[Vec<f64>]) -> Assignment {
+    let num_columns = cost.first().map_or(0, Vec::len);
+    assert!(
+        cost.iter().all(|row| row.len() == num_columns),
+        "rows of the cost matrix differ in length"
+    );
+    assert!(
+        cost.iter().flatten().all(|c| c.is_finite()),
+        "costs must be finite"
+    );
+
+    let mut columns = vec![None; cost.len()];
+
+    if cost.len() <= num_columns {
+        let row_to_column = assign(cost.len(), num_columns, |i, j| cost[i][j]);
+        row_to_column
+            .into_iter()
+            .enumerate()
+            .for_each(|(row, column)| columns[row] = Some(column));
+    } else {
+        let column_to_row = assign(num_columns, cost.len(), |i, j| cost[j][i]);
+        column_to_row
+            .into_iter()
+            .enumerate()
+            .for_each(|(column, row)| columns[row] = Some(column));
+    }
+
+    let total = columns
+        .iter()
+        .enumerate()
+        .filter_map(|(row, column)| column.map(|column| cost[row][column]))
+        .sum();
+
+    Assignment {
+        columns,
+        cost: total,
+    }
+}
+
+/// column of each of the n <= m rows; potentials u and v keep
+/// cost(i, j) - u[i] - v[j] >= 0 with equality on the assigned pairs, and
+/// each row is added by a Dijkstra-like search for the cheapest augmenting
+/// path. Index 0 stands for "no row" and "no column".
+fn assign(n: usize, m: usize, cost: impl Fn(usize, usize) -> f64) -> Vec<usize> {
+    let mut u = vec![0.0; n + 1];
+    let mut v = vec![0.0; m + 1];
+    // row + 1 assigned to column j, 0 if none
+    let mut row_of = vec![0; m + 1];
+    let mut way = vec![0; m + 1];
+
+    (1..=n).for_each(|i| {
+        row_of[0] = i;
+        let mut j0 = 0;
+        let mut min_slack = vec![f64::INFINITY; m + 1];
+        let mut used = vec![false; m + 1];
+
+        loop {
+            used[j0] = true;
+            let i0 = row_of[j0];
+            let mut delta = f64::INFINITY;
+            let mut j1 = 0;
+
+            (1..=m).filter(|&j| !used[j]).for_each(|j| {
+                let slack = cost(i0 - 1, j - 1) - u[i0] - v[j];
+                if slack < min_slack[j] {
+                    min_slack[j] = slack;
+                    way[j] = j0;
+                }
+                if min_slack[j] < delta {
+                    delta = min_slack[j];
+                    j1 = j;
+                }
+            });
+
+            (0..=m).for_each(|j| {
+                if used[j] {
+                    u[row_of[j]] += delta;
+                    v[j] -= delta;
+                } else {
+                    min_slack[j] -= delta;
+                }
+            });
+
+            j0 = j1;
+            if row_of[j0] == 0 {
+                break;
+            }
+        }
+
+        // flip the path that ends in the free column j0
+        while j0 != 0 {
+            let j1 = way[j0];
+            row_of[j0] = row_of[j1];
+            j0 = j1;
+        }
+    });
+
+    let mut column_of = vec![0; n];
+    (1..=m)
+        .filter(|&j| row_of[j] != 0)
+        .for_each(|j| column_of[row_of[j] - 1] = j - 1);
+
+    column_of
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{seq::SliceRandom, thread_rng, Rng};
+
+    use crate::graph::{
+        flow::{Dinic, FlowEdge, FlowNetwork, MaxFlow},
+        undirected_graph::read_graph,
+        TINY_G,
+    };
+
+    use super::*;
+
+    /// random bipartite graph on left 0..left and right left..left + right
+    fn random_bipartite(left: usize, right: usize, num_edges: usize) -> Graph {
+        let mut rng = thread_rng();
+        let mut g = Graph::new(left + right);
+
+        (0..num_edges).for_each(|_| {
+            g.add_edge(rng.gen_range(0..left), left + rng.gen_range(0..right));
+        });
+
+        g
+    }
+
+    /// maximum matching size through unit capacity max flow
+    fn flow_matching_size(g: &Graph, matching: &HopcroftKarp) -> usize {
+        let num_vertices = g.num_vertices();
+        let (s, t) = (num_vertices, num_vertices + 1);
+        let mut network = FlowNetwork::new(num_vertices + 2);
+
+        (0..num_vertices).for_each(|v| {
+            if matching.is_left(v) {
+                network.add_edge(FlowEdge::new(s, v, 1.0));
+                g.adj(v).for_each(|&w| {
+                    network.add_edge(FlowEdge::new(v, w, 1.0));
+                });
+            } else {
+                network.add_edge(FlowEdge::new(v, t, 1.0));
+            }
+        });
+
+        Dinic::new(&mut network, s, t).value() as usize
+    }
+
+    fn check(g: &Graph, matching: &HopcroftKarp) {
+        let pairs = matching.matching();
+        assert_eq!(pairs.len(), matching.size());
+
+        pairs.iter().for_each(|&(v, w)| {
+            assert!(matching.is_left(v) && !matching.is_left(w));
+            assert!(g.adj(v).any(|&x| x == w));
+            assert_eq!(matching.mate(v), Some(w));
+            assert_eq!(matching.mate(w), Some(v));
+        });
+
+        let matched = (0..g.num_vertices())
+            .filter(|&v| matching.is_matched(v))
+            .count();
+        assert_eq!(matched, 2 * matching.size());
+
+        let cover = matching.min_vertex_cover();
+        assert_eq!(cover.len(), matching.size());
+        (0..g.num_vertices()).for_each(|v| {
+            g.adj(v).for_each(|&w| {
+                assert!(matching.in_min_vertex_cover(v) || matching.in_min_vertex_cover(w))
+            })
+        });
+    }
+
+    #[test]
+    fn shifts() {
+        // people 0..4 and shifts 4..8, each person lists the shifts they can do
+        let g = read_graph("8 7  0 4  0 5  1 4  2 4  2 6  3 6  3 7".as_bytes()).unwrap();
+        let matching = HopcroftKarp::new(&g).unwrap();
+
+        check(&g, &matching);
+        assert_eq!(matching.size(), 4);
+        assert!(matching.is_perfect());
+        assert_eq!(matching.mate(1), Some(4));
+        assert_eq!(matching.mate(0), Some(5));
+
+        // nobody wants shifts 5 and 7, so only two people can work
+        let g = read_graph("8 5  0 4  1 4  2 4  2 6  3 6".as_bytes()).unwrap();
+        let matching = HopcroftKarp::new(&g).unwrap();
+
+        check(&g, &matching);
+        assert_eq!(matching.size(), 2);
+        assert!(!matching.is_perfect());
+        assert_eq!(matching.min_vertex_cover(), [4, 6]);
+    }
+
+    #[test]
+    fn not_bipartite() {
+        let g = read_graph(TINY_G.as_bytes()).unwrap();
+        let e = HopcroftKarp::new(&g).unwrap_err();
+
+        assert_eq!(e.odd_cycle.len() % 2, 0);
+        assert_eq!(e.odd_cycle.first(), e.odd_cycle.last());
+        assert!(e.to_string().starts_with("not bipartite, odd cycle "));
+    }
+
+    #[test]
+    fn random_graphs() {
+        let mut rng = thread_rng();
+
+        (0..200).for_each(|_| {
+            let g = random_bipartite(
+                rng.gen_range(1..15),
+                rng.gen_range(1..15),
+                rng.gen_range(0..40),
+            );
+            let matching = HopcroftKarp::new(&g).unwrap();
+
+            check(&g, &matching);
+            assert_eq!(matching.size(), flow_matching_size(&g, &matching));
+        });
+    }
+
+    #[test]
+    fn deep() {
+        // the path n - 1, 0, 1, ..., n - 2 where the first phase takes every
+        // edge v, v + 1 for even v, so the single augmenting path of the
+        // second phase runs its whole length
+        let n = 200_000;
+        let mut g = Graph::new(n);
+        g.add_edge(n - 1, 0);
+        (1..n - 1).for_each(|v| g.add_edge(v - 1, v));
+
+        let matching = HopcroftKarp::new(&g).unwrap();
+        assert_eq!(matching.size(), n / 2);
+        assert!(matching.is_perfect());
+    }
+
+    fn brute_force(cost: &[Vec<f64>]) -> f64 {
+        fn search(cost: &[Vec<f64>], row: usize, used: &mut [bool]) -> f64 {
+            if row == cost.len() {
+                return 0.0;
+            }
+
+            let mut best = f64::INFINITY;
+            for j in 0..used.len() {
+                if !used[j] {
+                    used[j] = true;
+                    best = best.min(cost[row][j] + search(cost, row + 1, used));
+                    used[j] = false;
+                }
+            }
+
+            best
+        }
+
+        if cost.len() <= cost.first().map_or(0, Vec::len) {
+            search(cost, 0, &mut vec![false; cost[0].len()])
+        } else {
+            let transposed: Vec<Vec<f64>> = (0..cost[0].len())
+                .map(|j| cost.iter().map(|row| row[j]).collect())
+                .collect();
+            search(&transposed, 0, &mut vec![false; cost.len()])
+        }
+    }
+
+    #[test]
+    fn assignment() {
+        let cost = vec![
+            vec![4.0, 1.0, 3.0],
+            vec![2.0, 0.0, 5.0],
+            vec![3.0, 2.0, 2.0],
+        ];
+        let assignment = hungarian(&cost);
+
+        assert_eq!(assignment.cost(), 5.0);
+        assert_eq!(
+            assignment.pairs().collect::<Vec<_>>(),
+            [(0, 1), (1, 0), (2, 2)]
+        );
+
+        // two people for three shifts and the other way round
+        let wide = vec![vec![7.0, 2.0, 9.0], vec![3.0, 8.0, 1.0]];
+        assert_eq!(hungarian(&wide).cost(), 3.0);
+
+        let tall = vec![vec![7.0, 3.0], vec![2.0, 8.0], vec![9.0, 1.0]];
+        let assignment = hungarian(&tall);
+        assert_eq!(assignment.cost(), 3.0);
+        assert_eq!(assignment.column(0), None);
+        assert_eq!(assignment.column(1), Some(0));
+        assert_eq!(assignment.column(2), Some(1));
+
+        assert_eq!(hungarian(&[]).cost(), 0.0);
+    }
+
+    #[test]
+    fn random_assignments() {
+        let mut rng = thread_rng();
+
+        (0..300).for_each(|_| {
+            let rows = rng.gen_range(1..7);
+            let columns = rng.gen_range(1..7);
+            let cost: Vec<Vec<f64>> = (0..rows)
+                .map(|_| {
+                    (0..columns)
+                        .map(|_| rng.gen_range(-20..50) as f64)
+                        .collect()
+                })
+                .collect();
+
+            let assignment = hungarian(&cost);
+            assert_eq!(assignment.cost(), brute_force(&cost));
+            assert_eq!(assignment.pairs().count(), rows.min(columns));
+
+            let mut used: Vec<usize> = assignment.pairs().map(|(_, column)| column).collect();
+            used.sort_unstable();
+            used.dedup();
+            assert_eq!(used.len(), rows.min(columns));
+        });
+
+        // a permutation matrix of zeros among ones is found exactly
+        let n = 50;
+        let mut permutation: Vec<usize> = (0..n).collect();
+        permutation.shuffle(&mut rng);
+        let cost: Vec<Vec<f64>> = (0..n)
+            .map(|i| {
+                (0..n)
+                    .map(|j| if permutation[i] == j { 0.0 } else { 1.0 })
+                    .collect()
+            })
+            .collect();
+
+        let assignment = hungarian(&cost);
+        assert_eq!(assignment.cost(), 0.0);
+        (0..n).for_each(|i| assert_eq!(assignment.column(i), Some(permutation[i])));
+    }
+}
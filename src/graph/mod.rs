@@ -1,35 +1,59 @@
-use std::{
-    fs::File,
-    io::{BufReader, Read},
-    path::Path,
-};
+use std::{fs::File, io::BufReader, ops::Range, path::Path};
 
-use itertools::Itertools;
+use crate::input::{read_edges, ParseError};
 
+pub mod biconnected;
+pub mod bipartite;
+pub mod connected_components;
+pub mod cycle;
 pub mod directed_graph;
 pub mod directed_symbol_graph;
+pub mod flow;
+pub mod incremental_topological;
+pub mod matching;
+pub mod search;
 pub mod strong_connected;
 pub mod topological;
+pub mod transitive_closure;
 pub mod undirected_graph;
 pub mod undirected_symbol_graph;
 
-pub fn parse_graph_params<P: AsRef<Path>>(path: P) -> (usize, Vec<(usize, usize)>) {
-    let mut input = String::new();
-    File::open(path).unwrap().read_to_string(&mut input);
+/// Read-only view shared by `Graph`, `Digraph` and the symbol graphs, so
+/// searches can be written once for all of them.
+pub trait GraphLike {
+    fn num_vertices(&self) -> usize;
 
-    let mut args = input.split_ascii_whitespace();
+    fn num_edges(&self) -> usize;
 
-    let num_vertices = args.next().unwrap().parse().unwrap();
+    /// vertices adjacent to v, pointed to by v for a digraph
+    fn adj(&self, v: usize) -> impl Iterator<Item = &usize>;
 
-    let edges = args
-        .skip(1)
-        .map(|s| s.parse::<usize>().unwrap())
-        .tuples()
-        .collect();
+    fn vertices(&self) -> Range<usize> {
+        0..self.num_vertices()
+    }
+}
 
-    (num_vertices, edges)
+pub fn parse_graph_params<P: AsRef<Path>>(
+    path: P,
+) -> Result<(usize, Vec<(usize, usize)>), ParseError> {
+    read_edges(BufReader::new(File::open(path)?))
 }
 
+/// tinyG.txt, for tests that shouldn't need algs4-data
+#[cfg(test)]
+pub(crate) const TINY_G: &str =
+    "13 13  0 5  4 3  0 1  9 12  6 4  5 4  0 2  11 12  9 10  0 6  7 8  9 11  5 3";
+
+/// tinyDG.txt
+#[cfg(test)]
+pub(crate) const TINY_DG: &str =
+    "13 22  4 2  2 3  3 2  6 0  0 1  2 0  11 12  12 9  9 10  9 11  7 9  \
+    10 12  11 4  4 3  3 5  6 8  8 6  5 4  0 5  6 4  6 9  7 6";
+
+/// a 3-cycle 0 1 2 leading to the 2-cycle 3 4, with 5 between them
+#[cfg(test)]
+pub(crate) const SMALL_DG: &str = "6 8  0 1  1 2  2 0  2 3  3 4  4 3  5 4  1 5";
+
 pub fn print_path(path: &[usize]) {
     println!(
         "{} to {}: {}",
@@ -0,0 +1,414 @@
+use std::ops::ControlFlow;
+
+use crate::collections::Queue;
+
+use super::GraphLike;
+
+/// What a depth-first search reports, in the order the recursive version
+/// would meet them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DfsEvent {
+    /// v is marked, preorder
+    Discover(usize),
+    /// v -> w leads to an unmarked w, which is discovered next
+    TreeEdge(usize, usize),
+    /// v -> w leads to a w that is already marked
+    NonTreeEdge(usize, usize),
+    /// every edge out of v is done, postorder
+    Finish(usize),
+}
+
+/// Depth-first search from s with an explicit stack of adjacency iterators,
+/// so deep graphs can't overflow the call stack. Vertices already in
+/// `marked` are not entered, which lets several searches share one array.
+/// Returning `ControlFlow::Break` from `visit` stops the search.
+pub fn depth_first<G, F>(g: &G, s: usize, marked: &mut [bool], mut visit: F) -> ControlFlow<()>
+where
+    G: GraphLike,
+    F: FnMut(DfsEvent) -> ControlFlow<()>,
+{
+    if marked[s] {
+        return ControlFlow::Continue(());
+    }
+
+    marked[s] = true;
+    visit(DfsEvent::Discover(s))?;
+
+    let mut stack = vec![(s, g.adj(s))];
+
+    while let Some((v, adj)) = stack.last_mut() {
+        let v = *v;
+
+        match adj.next().copied() {
+            Some(w) if !marked[w] => {
+                visit(DfsEvent::TreeEdge(v, w))?;
+                marked[w] = true;
+                visit(DfsEvent::Discover(w))?;
+                stack.push((w, g.adj(w)));
+            }
+            Some(w) => visit(DfsEvent::NonTreeEdge(v, w))?,
+            None => {
+                stack.pop();
+                visit(DfsEvent::Finish(v))?;
+            }
+        }
+    }
+
+    ControlFlow::Continue(())
+}
+
+pub trait Search {
+    /// find vertices connected to a source vertex s
+    fn new<G: GraphLike>(g: &G, s: usize) -> Self;
+
+    /// is v connected to s?
+    fn marked(&self, v: usize) -> bool;
+
+    /// how many vertices are connected to s?
+    fn count(&self) -> usize;
+}
+
+pub struct DepthFirstSearch {
+    marked: Vec<bool>,
+    count: usize,
+}
+
+impl DepthFirstSearch {
+    fn visit<G: GraphLike>(&mut self, g: &G, v: usize) {
+        let count = &mut self.count;
+
+        depth_first(g, v, &mut self.marked, |event| {
+            if let DfsEvent::Discover(_) = event {
+                *count += 1;
+            }
+
+            ControlFlow::Continue(())
+        });
+    }
+}
+
+impl DepthFirstSearch {
+    /// vertices reachable from any of the sources
+    pub fn from_sources<G, I>(g: &G, sources: I) -> Self
+    where
+        G: GraphLike,
+        I: IntoIterator<Item = usize>,
+    {
+        let mut dfs = DepthFirstSearch {
+            marked: vec![false; g.num_vertices()],
+            count: 0,
+        };
+
+        sources.into_iter().for_each(|s| dfs.visit(g, s));
+
+        dfs
+    }
+}
+
+impl Search for DepthFirstSearch {
+    fn new<G: GraphLike>(g: &G, s: usize) -> Self {
+        let mut dfs = DepthFirstSearch {
+            marked: vec![false; g.num_vertices()],
+            count: 0,
+        };
+
+        dfs.visit(g, s);
+
+        dfs
+    }
+
+    fn marked(&self, v: usize) -> bool {
+        self.marked[v]
+    }
+
+    fn count(&self) -> usize {
+        self.count
+    }
+}
+
+pub trait Paths {
+    /// find paths in G from source s
+    fn new<G: GraphLike>(g: &G, s: usize) -> Self;
+
+    /// path from s to v; null if no such path
+    fn path_to(&self, v: usize) -> Option<Vec<usize>>;
+}
+
+#[derive(Debug)]
+pub struct DepthFirstPaths {
+    marked: Vec<bool>,
+    edge_to: Vec<Option<usize>>,
+    s: usize,
+}
+
+impl DepthFirstPaths {
+    fn visit<G: GraphLike>(&mut self, g: &G, v: usize) {
+        let edge_to = &mut self.edge_to;
+
+        depth_first(g, v, &mut self.marked, |event| {
+            if let DfsEvent::TreeEdge(v, w) = event {
+                edge_to[w] = Some(v);
+            }
+
+            ControlFlow::Continue(())
+        });
+    }
+}
+
+impl Paths for DepthFirstPaths {
+    fn new<G: GraphLike>(g: &G, s: usize) -> Self {
+        let num_vertices = g.num_vertices();
+
+        let mut dfp = DepthFirstPaths {
+            marked: vec![false; num_vertices],
+            edge_to: vec![None; num_vertices],
+            s,
+        };
+
+        dfp.visit(g, s);
+
+        dfp
+    }
+
+    fn path_to(&self, mut v: usize) -> Option<Vec<usize>> {
+        if !self.marked[v] {
+            return None;
+        }
+
+        let mut path = Vec::new();
+
+        while let Some(from) = self.edge_to[v] {
+            path.push(v);
+            v = from;
+        }
+
+        path.push(self.s);
+
+        path.reverse();
+
+        Some(path)
+    }
+}
+
+#[derive(Debug)]
+pub struct BreadthFirstPaths {
+    marked: Vec<bool>,
+    edge_to: Vec<Option<usize>>,
+    s: usize,
+}
+
+impl BreadthFirstPaths {
+    fn visit<G: GraphLike>(&mut self, g: &G, v: usize) {
+        self.marked[v] = true;
+        let mut queue = Queue::new();
+        queue.enqueue(v);
+
+        while let Some(v) = queue.dequeue() {
+            g.adj(v).for_each(|&w| {
+                if !self.marked[w] {
+                    self.edge_to[w] = Some(v);
+                    self.marked[w] = true;
+                    queue.enqueue(w);
+                }
+            });
+        }
+    }
+}
+
+impl Paths for BreadthFirstPaths {
+    fn new<G: GraphLike>(g: &G, s: usize) -> Self {
+        let num_vertices = g.num_vertices();
+
+        let mut dfp = BreadthFirstPaths {
+            marked: vec![false; num_vertices],
+            edge_to: vec![None; num_vertices],
+            s,
+        };
+
+        dfp.visit(g, s);
+
+        dfp
+    }
+
+    fn path_to(&self, mut v: usize) -> Option<Vec<usize>> {
+        if !self.marked[v] {
+            return None;
+        }
+
+        let mut path = Vec::new();
+
+        while let Some(from) = self.edge_to[v] {
+            path.push(v);
+            v = from;
+        }
+
+        path.push(self.s);
+
+        path.reverse();
+
+        Some(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{thread_rng, Rng};
+
+    use crate::graph::{
+        directed_graph::{read_digraph, Digraph},
+        directed_symbol_graph::SymbolDigraph,
+        undirected_graph::{read_graph, Graph},
+        SMALL_DG,
+    };
+
+    use super::*;
+
+    /// the textbook recursion, as a reference for `depth_first`
+    fn recursive<G: GraphLike>(g: &G, v: usize, marked: &mut [bool], events: &mut Vec<DfsEvent>) {
+        marked[v] = true;
+        events.push(DfsEvent::Discover(v));
+
+        g.adj(v).for_each(|&w| {
+            if !marked[w] {
+                events.push(DfsEvent::TreeEdge(v, w));
+                recursive(g, w, marked, events);
+            } else {
+                events.push(DfsEvent::NonTreeEdge(v, w));
+            }
+        });
+
+        events.push(DfsEvent::Finish(v));
+    }
+
+    fn assert_same_events<G: GraphLike>(g: &G) {
+        let mut expected = Vec::new();
+        let mut marked = vec![false; g.num_vertices()];
+        g.vertices().for_each(|v| {
+            if !marked[v] {
+                recursive(g, v, &mut marked, &mut expected);
+            }
+        });
+
+        let mut events = Vec::new();
+        let mut marked = vec![false; g.num_vertices()];
+        g.vertices().for_each(|v| {
+            depth_first(g, v, &mut marked, |event| {
+                events.push(event);
+                ControlFlow::Continue(())
+            });
+        });
+
+        assert_eq!(events, expected);
+    }
+
+    #[test]
+    fn matches_recursive() {
+        let mut rng = thread_rng();
+
+        (0..50).for_each(|_| {
+            let num_vertices = rng.gen_range(1..40);
+            let mut g = Graph::new(num_vertices);
+            let mut dg = Digraph::new(num_vertices);
+
+            (0..rng.gen_range(0..3 * num_vertices)).for_each(|_| {
+                let (v, w) = (
+                    rng.gen_range(0..num_vertices),
+                    rng.gen_range(0..num_vertices),
+                );
+                g.add_edge(v, w);
+                dg.add_edge(v, w);
+            });
+
+            assert_same_events(&g);
+            assert_same_events(&dg);
+        });
+    }
+
+    #[test]
+    fn stops_early() {
+        let g = read_digraph(SMALL_DG.as_bytes()).unwrap();
+        let mut marked = vec![false; g.num_vertices()];
+        let mut discovered = Vec::new();
+
+        let flow = depth_first(&g, 0, &mut marked, |event| match event {
+            DfsEvent::Discover(3) => ControlFlow::Break(()),
+            DfsEvent::Discover(v) => {
+                discovered.push(v);
+                ControlFlow::Continue(())
+            }
+            _ => ControlFlow::Continue(()),
+        });
+
+        assert!(flow.is_break());
+        assert!(marked[3]);
+        assert!(!discovered.contains(&3));
+    }
+
+    #[test]
+    fn deep_path() {
+        let n = 1_000_000;
+        let mut g = Graph::new(n);
+        (1..n).for_each(|v| g.add_edge(v - 1, v));
+
+        let dfs = DepthFirstSearch::new(&g, 0);
+        assert_eq!(dfs.count(), n);
+
+        let dfp = DepthFirstPaths::new(&g, n - 1);
+        assert_eq!(dfp.path_to(0).unwrap().len(), n);
+    }
+
+    fn reachable<S: Search>(search: &S, num_vertices: usize) -> Vec<usize> {
+        (0..num_vertices).filter(|&v| search.marked(v)).collect()
+    }
+
+    #[test]
+    fn undirected_and_directed() {
+        let g = read_graph(SMALL_DG.as_bytes()).unwrap();
+        let dg = read_digraph(SMALL_DG.as_bytes()).unwrap();
+
+        let dfs = DepthFirstSearch::new(&g, 4);
+        assert_eq!(dfs.count(), 6);
+
+        let dfs = DepthFirstSearch::new(&dg, 4);
+        assert_eq!(reachable(&dfs, dg.num_vertices()), [3, 4]);
+        assert_eq!(dfs.count(), 2);
+
+        let dfs = DepthFirstSearch::new(&dg, 5);
+        assert_eq!(reachable(&dfs, dg.num_vertices()), [3, 4, 5]);
+    }
+
+    #[test]
+    fn paths() {
+        let dg = read_digraph(SMALL_DG.as_bytes()).unwrap();
+
+        let bfp = BreadthFirstPaths::new(&dg, 0);
+        assert_eq!(bfp.path_to(0), Some(vec![0]));
+        assert_eq!(bfp.path_to(4), Some(vec![0, 1, 5, 4]));
+
+        let dfp = DepthFirstPaths::new(&dg, 2);
+        let path = dfp.path_to(5).unwrap();
+        assert_eq!(path.first(), Some(&2));
+        assert_eq!(path.last(), Some(&5));
+        assert!(path.windows(2).all(|e| dg.adj(e[0]).any(|&w| w == e[1])));
+
+        let dfp = DepthFirstPaths::new(&dg, 3);
+        assert_eq!(dfp.path_to(0), None);
+    }
+
+    #[test]
+    fn symbol_graph() {
+        let input = "JFK MCO\nORD DEN\nORD HOU\nDEN PHX\nJFK ATL";
+        let sg = SymbolDigraph::new(input.lines().map(|line| line.split(' ')));
+
+        let bfp = BreadthFirstPaths::new(&sg, sg.index("ORD"));
+        let path: Vec<&str> = bfp
+            .path_to(sg.index("PHX"))
+            .unwrap()
+            .into_iter()
+            .map(|v| sg.name(v))
+            .collect();
+
+        assert_eq!(path, ["ORD", "DEN", "PHX"]);
+        assert!(bfp.path_to(sg.index("JFK")).is_none());
+    }
+}
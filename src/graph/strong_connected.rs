@@ -1,5 +1,65 @@
-use super::{directed_graph::Digraph, topological::DepthFirstOrder};
+use std::{collections::HashSet, ops::ControlFlow};
 
+use super::{
+    directed_graph::Digraph,
+    search::{depth_first, DfsEvent},
+    topological::DepthFirstOrder,
+};
+
+/// Strong components of a digraph, numbered 0 to count - 1. All three
+/// implementations number them in reverse topological order of the
+/// condensation: no edge leads from a component to a higher numbered one.
+pub trait StronglyConnected {
+    fn new(g: &Digraph) -> Self;
+
+    /// number of strong components
+    fn count(&self) -> usize;
+
+    /// component of every vertex
+    fn ids(&self) -> &[usize];
+
+    fn id(&self, v: usize) -> usize {
+        self.ids()[v]
+    }
+
+    fn strongly_connected(&self, v: usize, w: usize) -> bool {
+        self.id(v) == self.id(w)
+    }
+
+    /// vertices of every component, indexed by id
+    fn components(&self) -> Vec<Vec<usize>> {
+        let mut components = vec![Vec::new(); self.count()];
+
+        self.ids()
+            .iter()
+            .enumerate()
+            .for_each(|(v, &id)| components[id].push(v));
+
+        components
+    }
+
+    /// The kernel DAG: one vertex per component and an edge between two
+    /// components whenever g has an edge between them, without duplicates.
+    /// Returned with the component of every vertex of g.
+    fn condensation(&self, g: &Digraph) -> (Digraph, Vec<usize>) {
+        let mut dag = Digraph::new(self.count());
+        let mut seen = HashSet::new();
+
+        g.vertices().for_each(|v| {
+            g.adj(v).for_each(|&w| {
+                let edge = (self.id(v), self.id(w));
+                if edge.0 != edge.1 && seen.insert(edge) {
+                    dag.add_edge(edge.0, edge.1);
+                }
+            });
+        });
+
+        (dag, self.ids().to_vec())
+    }
+}
+
+/// Kosaraju-Sharir: a depth-first search of g in the reverse postorder of
+/// its reverse.
 #[derive(Debug)]
 pub struct SCC {
     pub count: usize,
@@ -9,17 +69,20 @@ pub struct SCC {
 
 impl SCC {
     fn search(&mut self, g: &Digraph, v: usize) {
-        self.marked[v] = true;
-        self.id[v] = self.count;
+        let SCC { id, count, .. } = self;
 
-        g.adj(v).for_each(|&w| {
-            if !self.marked[w] {
-                self.search(g, w);
+        depth_first(g, v, &mut self.marked, |event| {
+            if let DfsEvent::Discover(v) = event {
+                id[v] = *count;
             }
+
+            ControlFlow::Continue(())
         });
     }
+}
 
-    pub fn new(g: &Digraph) -> Self {
+impl StronglyConnected for SCC {
+    fn new(g: &Digraph) -> Self {
         let num_vertices = g.num_vertices();
         let mut scc = SCC {
             count: 0,
@@ -40,12 +103,152 @@ impl SCC {
         scc
     }
 
-    fn strongly_connected(&self, v: usize, w: usize) -> bool {
-        self.id[v] == self.id[w]
+    fn count(&self) -> usize {
+        self.count
     }
 
-    fn id(&self, v: usize) -> usize {
-        self.id[v]
+    fn ids(&self) -> &[usize] {
+        &self.id
+    }
+}
+
+/// Tarjan: one depth-first search. A vertex whose subtree reaches no
+/// vertex discovered before it, through vertices still on the stack, is the
+/// root of a component made of everything above it on the stack.
+#[derive(Debug)]
+pub struct TarjanSCC {
+    count: usize,
+    id: Vec<usize>,
+}
+
+impl StronglyConnected for TarjanSCC {
+    fn new(g: &Digraph) -> Self {
+        let num_vertices = g.num_vertices();
+        let mut marked = vec![false; num_vertices];
+        let mut id: Vec<Option<usize>> = vec![None; num_vertices];
+        let mut pre = vec![0; num_vertices];
+        let mut low = vec![0; num_vertices];
+        let mut parent: Vec<Option<usize>> = vec![None; num_vertices];
+        let mut stack = Vec::new();
+        let mut counter = 0;
+        let mut count = 0;
+
+        g.vertices().for_each(|s| {
+            depth_first(g, s, &mut marked, |event| {
+                match event {
+                    DfsEvent::Discover(v) => {
+                        pre[v] = counter;
+                        low[v] = counter;
+                        counter += 1;
+                        stack.push(v);
+                    }
+                    DfsEvent::TreeEdge(v, w) => parent[w] = Some(v),
+                    DfsEvent::NonTreeEdge(v, w) if id[w].is_none() => {
+                        low[v] = low[v].min(pre[w]);
+                    }
+                    DfsEvent::NonTreeEdge(..) => {}
+                    DfsEvent::Finish(v) => {
+                        if low[v] == pre[v] {
+                            while let Some(w) = stack.pop() {
+                                id[w] = Some(count);
+                                if w == v {
+                                    break;
+                                }
+                            }
+                            count += 1;
+                        }
+
+                        if let Some(p) = parent[v] {
+                            low[p] = low[p].min(low[v]);
+                        }
+                    }
+                }
+
+                ControlFlow::Continue(())
+            });
+        });
+
+        TarjanSCC {
+            count,
+            id: id.into_iter().map(Option::unwrap).collect(),
+        }
+    }
+
+    fn count(&self) -> usize {
+        self.count
+    }
+
+    fn ids(&self) -> &[usize] {
+        &self.id
+    }
+}
+
+/// Gabow's path-based algorithm: like Tarjan, but instead of low numbers a
+/// second stack holds the boundaries between the components still being
+/// merged along the current path.
+#[derive(Debug)]
+pub struct GabowSCC {
+    count: usize,
+    id: Vec<usize>,
+}
+
+impl StronglyConnected for GabowSCC {
+    fn new(g: &Digraph) -> Self {
+        let num_vertices = g.num_vertices();
+        let mut marked = vec![false; num_vertices];
+        let mut id: Vec<Option<usize>> = vec![None; num_vertices];
+        let mut pre = vec![0; num_vertices];
+        let mut vertices = Vec::new();
+        let mut roots: Vec<usize> = Vec::new();
+        let mut counter = 0;
+        let mut count = 0;
+
+        g.vertices().for_each(|s| {
+            depth_first(g, s, &mut marked, |event| {
+                match event {
+                    DfsEvent::Discover(v) => {
+                        pre[v] = counter;
+                        counter += 1;
+                        vertices.push(v);
+                        roots.push(v);
+                    }
+                    // w is on the current path's components, so everything
+                    // discovered after it joins its component
+                    DfsEvent::NonTreeEdge(_, w) if id[w].is_none() => {
+                        while roots.last().is_some_and(|&r| pre[r] > pre[w]) {
+                            roots.pop();
+                        }
+                    }
+                    DfsEvent::Finish(v) if roots.last() == Some(&v) => {
+                        roots.pop();
+
+                        while let Some(w) = vertices.pop() {
+                            id[w] = Some(count);
+                            if w == v {
+                                break;
+                            }
+                        }
+                        count += 1;
+                    }
+                    _ => {}
+                }
+
+                ControlFlow::Continue(())
+            });
+        });
+
+        GabowSCC {
+            count,
+            id: id.into_iter().map(Option::unwrap).collect(),
+        }
+    }
+
+    fn count(&self) -> usize {
+        self.count
+    }
+
+    fn ids(&self) -> &[usize] {
+        &self.id
     }
 }
 
@@ -53,10 +256,110 @@ impl SCC {
 mod tests {
     use itertools::Itertools;
 
-    use crate::graph::directed_graph::create_digraph_from_path;
+    use rand::{thread_rng, Rng};
+
+    use crate::graph::{
+        directed_graph::{create_digraph_from_path, read_digraph, DirectedCycle},
+        search::{DepthFirstSearch, Search},
+        TINY_DG,
+    };
 
     use super::*;
 
+    fn assert_tiny_dg<S: StronglyConnected>() {
+        let g = read_digraph(TINY_DG.as_bytes()).unwrap();
+        let scc = S::new(&g);
+
+        let mut components = scc.components();
+        components.sort();
+        assert_eq!(
+            components,
+            [
+                vec![0, 2, 3, 4, 5],
+                vec![1],
+                vec![6, 8],
+                vec![7],
+                vec![9, 10, 11, 12]
+            ]
+        );
+        assert_eq!(scc.count(), 5);
+        assert!(scc.strongly_connected(6, 8));
+        assert!(!scc.strongly_connected(7, 9));
+
+        let (dag, component) = scc.condensation(&g);
+        assert_eq!(component, scc.ids());
+        assert_eq!(dag.num_vertices(), 5);
+        // 7 -> 6 8 -> 9 .. 12 -> 0 .. 5 -> 1, plus 7 -> 9 .. 12 and 6 8 -> 0 .. 5
+        assert_eq!(dag.num_edges(), 6);
+        assert!(DirectedCycle::new(&dag).cycle.is_none());
+    }
+
+    #[test]
+    fn tiny_dg() {
+        assert_tiny_dg::<SCC>();
+        assert_tiny_dg::<TarjanSCC>();
+        assert_tiny_dg::<GabowSCC>();
+    }
+
+    /// v and w are strongly connected iff each reaches the other.
+    #[test]
+    fn random() {
+        let mut rng = thread_rng();
+
+        (0..100).for_each(|_| {
+            let n = rng.gen_range(1..30);
+            let mut g = Digraph::new(n);
+            (0..rng.gen_range(0..3 * n)).for_each(|_| {
+                g.add_edge(rng.gen_range(0..n), rng.gen_range(0..n));
+            });
+
+            let reach: Vec<DepthFirstSearch> =
+                (0..n).map(|v| DepthFirstSearch::new(&g, v)).collect();
+
+            let kosaraju = SCC::new(&g);
+            let tarjan = TarjanSCC::new(&g);
+            let gabow = GabowSCC::new(&g);
+
+            (0..n).for_each(|v| {
+                (0..n).for_each(|w| {
+                    let expected = reach[v].marked(w) && reach[w].marked(v);
+                    assert_eq!(kosaraju.strongly_connected(v, w), expected);
+                    assert_eq!(tarjan.strongly_connected(v, w), expected);
+                    assert_eq!(gabow.strongly_connected(v, w), expected);
+                });
+            });
+
+            // components are numbered in reverse topological order
+            [kosaraju.ids(), tarjan.ids(), gabow.ids()]
+                .iter()
+                .for_each(|ids| {
+                    g.vertices()
+                        .for_each(|v| g.adj(v).for_each(|&w| assert!(ids[v] >= ids[w])));
+                });
+
+            let (dag, _) = tarjan.condensation(&g);
+            assert_eq!(GabowSCC::new(&dag).count(), dag.num_vertices());
+        });
+    }
+
+    #[test]
+    fn deep_cycle() {
+        let n = 1_000_000;
+        let mut g = Digraph::new(n);
+        (1..n).for_each(|v| g.add_edge(v - 1, v));
+
+        assert_eq!(SCC::new(&g).count, n);
+        assert_eq!(TarjanSCC::new(&g).count(), n);
+        assert_eq!(GabowSCC::new(&g).count(), n);
+
+        g.add_edge(n - 1, 0);
+        let scc = SCC::new(&g);
+        assert_eq!(scc.count, 1);
+        assert!(scc.strongly_connected(0, n - 1));
+        assert_eq!(TarjanSCC::new(&g).count(), 1);
+        assert_eq!(GabowSCC::new(&g).count(), 1);
+    }
+
     #[test]
     fn scc() {
         let g = create_digraph_from_path("./algs4-data/tinyDG.txt");
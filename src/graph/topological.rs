@@ -1,26 +1,79 @@
 use core::num;
-use std::collections::VecDeque;
+use std::{collections::VecDeque, error::Error, fmt::Display, ops::ControlFlow};
 
-use super::directed_graph::{Digraph, DirectedCycle};
+use crate::{
+    collections::Queue,
+    sort::priority_queue::{HeapMinPQ, MinPQ},
+};
+
+use super::{
+    cycle::path_up,
+    directed_graph::Digraph,
+    search::{depth_first, DfsEvent},
+};
+
+/// Kind of an edge v -> w relative to a depth-first search forest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EdgeKind {
+    /// w was discovered through this edge
+    Tree,
+    /// w is an ancestor of v (or v itself), so the edge closes a cycle
+    Back,
+    /// w is a descendant of v reached earlier through another path
+    Forward,
+    /// w is in a subtree that was already finished
+    Cross,
+}
 
 pub struct DepthFirstOrder {
     pre: Vec<usize>,
     post: Vec<usize>,
+    /// position of every vertex in pre and post
+    pre_number: Vec<usize>,
+    post_number: Vec<usize>,
+    edges: Vec<(usize, usize, EdgeKind)>,
     marked: Vec<bool>,
 }
 
 impl DepthFirstOrder {
     fn search(&mut self, g: &Digraph, v: usize) {
-        self.pre.push(v);
-        self.marked[v] = true;
+        let DepthFirstOrder {
+            pre,
+            post,
+            pre_number,
+            post_number,
+            edges,
+            ..
+        } = self;
 
-        g.adj(v).for_each(|&w| {
-            if !self.marked[w] {
-                self.search(g, w);
+        depth_first(g, v, &mut self.marked, |event| {
+            match event {
+                DfsEvent::Discover(v) => {
+                    pre_number[v] = pre.len();
+                    pre.push(v);
+                }
+                DfsEvent::TreeEdge(v, w) => edges.push((v, w, EdgeKind::Tree)),
+                DfsEvent::NonTreeEdge(v, w) => {
+                    // every vertex is marked on discovery, so an unfinished w
+                    // is still on the stack
+                    let kind = if post_number[w] == usize::MAX {
+                        EdgeKind::Back
+                    } else if pre_number[w] > pre_number[v] {
+                        EdgeKind::Forward
+                    } else {
+                        EdgeKind::Cross
+                    };
+
+                    edges.push((v, w, kind));
+                }
+                DfsEvent::Finish(v) => {
+                    post_number[v] = post.len();
+                    post.push(v);
+                }
             }
-        });
 
-        self.post.push(v);
+            ControlFlow::Continue(())
+        });
     }
 
     pub fn new(g: &Digraph) -> Self {
@@ -29,6 +82,9 @@ impl DepthFirstOrder {
         let mut dfo = DepthFirstOrder {
             pre: Vec::with_capacity(num_vertices),
             post: Vec::with_capacity(num_vertices),
+            pre_number: vec![usize::MAX; num_vertices],
+            post_number: vec![usize::MAX; num_vertices],
+            edges: Vec::with_capacity(g.num_edges()),
             marked: vec![false; num_vertices],
         };
 
@@ -41,50 +97,490 @@ impl DepthFirstOrder {
         dfo
     }
 
+    /// vertices in the order they were discovered
+    pub fn preorder(&self) -> impl DoubleEndedIterator<Item = usize> + '_ {
+        self.pre.iter().copied()
+    }
+
+    /// vertices in the order they were finished
+    pub fn postorder(&self) -> impl DoubleEndedIterator<Item = usize> + '_ {
+        self.post.iter().copied()
+    }
+
+    /// a topological order if the digraph has no cycle
+    pub fn reverse_postorder(&self) -> impl DoubleEndedIterator<Item = usize> + '_ {
+        self.post.iter().rev().copied()
+    }
+
     pub fn reverse_post(&self) -> Vec<usize> {
-        let mut result = self.post.clone();
+        self.reverse_postorder().collect()
+    }
+
+    /// position of v in preorder
+    pub fn pre(&self, v: usize) -> usize {
+        self.pre_number[v]
+    }
+
+    /// position of v in postorder
+    pub fn post(&self, v: usize) -> usize {
+        self.post_number[v]
+    }
 
-        result.reverse();
+    /// every edge with its kind, in the order the search examined them
+    pub fn classified_edges(&self) -> &[(usize, usize, EdgeKind)] {
+        &self.edges
+    }
 
-        result
+    /// edges closing a cycle, none exactly when the digraph is acyclic
+    pub fn back_edges(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.edges
+            .iter()
+            .filter(|(_, _, kind)| *kind == EdgeKind::Back)
+            .map(|&(v, w, _)| (v, w))
     }
 }
 
+/// A directed cycle, the reason a digraph has no topological order. The
+/// first vertex is repeated at the end and every step follows an edge.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cycle {
+    pub vertices: Vec<usize>,
+}
+
+impl Cycle {
+    fn find(g: &Digraph) -> Self {
+        topological_sort(g).expect_err("a digraph without topological order has a cycle")
+    }
+}
+
+impl Display for Cycle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "cycle {}",
+            self.vertices
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<String>>()
+                .join(" -> ")
+        )
+    }
+}
+
+impl Error for Cycle {}
+
 #[derive(Debug)]
-struct Topological {
+pub struct Topological {
     order: Option<Vec<usize>>,
+    /// position of every vertex in order
+    rank: Vec<usize>,
 }
 
 impl Topological {
-    fn new(g: &Digraph) -> Self {
-        let dc = DirectedCycle::new(g);
+    pub fn new(g: &Digraph) -> Self {
+        let order = topological_sort(g).ok();
+
+        let mut rank = vec![0; g.num_vertices()];
+        if let Some(order) = &order {
+            order.iter().enumerate().for_each(|(i, &v)| rank[v] = i);
+        }
 
-        let order = if dc.cycle.is_none() {
-            let dfo = DepthFirstOrder::new(g);
+        Topological { order, rank }
+    }
 
-            Some(dfo.reverse_post())
-        } else {
-            None
-        };
+    pub fn has_order(&self) -> bool {
+        self.order.is_some()
+    }
+
+    /// vertices such that every edge points forward, None for a cyclic digraph
+    pub fn order(&self) -> Option<&[usize]> {
+        self.order.as_deref()
+    }
+
+    /// position of v in the order
+    pub fn rank(&self, v: usize) -> Option<usize> {
+        self.order.as_ref().map(|_| self.rank[v])
+    }
+}
+
+/// Reverse postorder of a depth-first search, or the cycle closed by the
+/// first back edge it meets, in a single pass.
+pub fn topological_sort(g: &Digraph) -> Result<Vec<usize>, Cycle> {
+    let num_vertices = g.num_vertices();
+    let mut marked = vec![false; num_vertices];
+    let mut on_stack = vec![false; num_vertices];
+    let mut edge_to: Vec<Option<usize>> = vec![None; num_vertices];
+    let mut post = Vec::with_capacity(num_vertices);
+    let mut cycle = None;
+
+    g.vertices().try_for_each(|s| {
+        depth_first(g, s, &mut marked, |event| match event {
+            DfsEvent::Discover(v) => {
+                on_stack[v] = true;
+                ControlFlow::Continue(())
+            }
+            DfsEvent::TreeEdge(v, w) => {
+                edge_to[w] = Some(v);
+                ControlFlow::Continue(())
+            }
+            DfsEvent::NonTreeEdge(v, w) if on_stack[w] => {
+                cycle = Some(path_up(&edge_to, v, w));
+                ControlFlow::Break(())
+            }
+            DfsEvent::NonTreeEdge(..) => ControlFlow::Continue(()),
+            DfsEvent::Finish(v) => {
+                on_stack[v] = false;
+                post.push(v);
+                ControlFlow::Continue(())
+            }
+        })
+    });
+
+    match cycle {
+        Some(vertices) => Err(Cycle { vertices }),
+        None => {
+            post.reverse();
+            Ok(post)
+        }
+    }
+}
+
+/// Vertices with no remaining incoming edge, ready to be output.
+enum Sources {
+    Fifo(Queue<usize>),
+    Smallest(HeapMinPQ<usize>),
+}
+
+impl Sources {
+    fn push(&mut self, v: usize) {
+        match self {
+            Sources::Fifo(queue) => queue.enqueue(v),
+            Sources::Smallest(pq) => pq.insert(v),
+        }
+    }
+
+    fn pop(&mut self) -> Option<usize> {
+        match self {
+            Sources::Fifo(queue) => queue.dequeue(),
+            Sources::Smallest(pq) if pq.is_empty() => None,
+            Sources::Smallest(pq) => Some(pq.delete_min()),
+        }
+    }
+}
+
+fn in_degrees(g: &Digraph) -> Vec<usize> {
+    let mut in_degree = vec![0; g.num_vertices()];
+    g.vertices()
+        .for_each(|v| g.adj(v).for_each(|&w| in_degree[w] += 1));
+
+    in_degree
+}
+
+fn kahn_with(g: &Digraph, mut sources: Sources) -> Result<Vec<usize>, Cycle> {
+    let mut in_degree = in_degrees(g);
+    let mut order = Vec::with_capacity(g.num_vertices());
+
+    g.vertices()
+        .filter(|&v| in_degree[v] == 0)
+        .for_each(|v| sources.push(v));
+
+    while let Some(v) = sources.pop() {
+        order.push(v);
+
+        g.adj(v).for_each(|&w| {
+            in_degree[w] -= 1;
+            if in_degree[w] == 0 {
+                sources.push(w);
+            }
+        });
+    }
 
-        Topological { order }
+    // whatever is left sits on or behind a cycle
+    if order.len() < g.num_vertices() {
+        return Err(Cycle::find(g));
     }
+
+    Ok(order)
+}
+
+/// Kahn's algorithm: repeatedly output a vertex without incoming edges,
+/// first in first out.
+pub fn kahn(g: &Digraph) -> Result<Vec<usize>, Cycle> {
+    kahn_with(g, Sources::Fifo(Queue::new()))
+}
+
+/// Kahn's algorithm always taking the smallest available vertex, which gives
+/// the lexicographically smallest topological order.
+pub fn kahn_smallest_first(g: &Digraph) -> Result<Vec<usize>, Cycle> {
+    kahn_with(g, Sources::Smallest(HeapMinPQ::new()))
+}
+
+/// Group the vertices by the length of the longest path reaching them:
+/// layer 0 has no incoming edges and every edge goes to a later layer, so
+/// the jobs of one layer can run in parallel once the earlier ones are done.
+/// Each layer is sorted.
+pub fn layers(g: &Digraph) -> Result<Vec<Vec<usize>>, Cycle> {
+    let mut in_degree = in_degrees(g);
+    let mut layers = Vec::new();
+    let mut done = 0;
+
+    let mut layer: Vec<usize> = g.vertices().filter(|&v| in_degree[v] == 0).collect();
+
+    while !layer.is_empty() {
+        done += layer.len();
+
+        let mut next: Vec<usize> = layer
+            .iter()
+            .flat_map(|&v| g.adj(v))
+            .filter(|&&w| {
+                in_degree[w] -= 1;
+                in_degree[w] == 0
+            })
+            .copied()
+            .collect();
+        next.sort_unstable();
+
+        layers.push(layer);
+        layer = next;
+    }
+
+    if done < g.num_vertices() {
+        return Err(Cycle::find(g));
+    }
+
+    Ok(layers)
 }
 
 #[cfg(test)]
 mod tests {
 
-    use crate::graph::directed_symbol_graph::{create_symbol_digraph, SymbolDigraph};
+    use rand::{thread_rng, Rng};
+
+    use crate::graph::{
+        directed_graph::{read_digraph, DirectedCycle},
+        directed_symbol_graph::{create_symbol_digraph, SymbolDigraph},
+        TINY_DG,
+    };
 
     use super::*;
 
+    const TINY_DAG: &str =
+        "13 15  2 3  0 6  0 1  2 0  11 12  9 12  9 10  9 11  3 5  8 7  5 4  0 5  6 4  6 9  7 6";
+
+    fn assert_order(g: &Digraph, order: &[usize]) {
+        let mut rank = vec![None; g.num_vertices()];
+        order
+            .iter()
+            .enumerate()
+            .for_each(|(i, &v)| rank[v] = Some(i));
+
+        assert!(rank.iter().all(Option::is_some));
+        g.vertices()
+            .for_each(|v| g.adj(v).for_each(|&w| assert!(rank[v] < rank[w])));
+    }
+
+    fn assert_cycle(g: &Digraph, cycle: &Cycle) {
+        let vertices = &cycle.vertices;
+        assert_eq!(vertices.first(), vertices.last());
+        vertices
+            .windows(2)
+            .for_each(|e| assert!(g.adj(e[0]).any(|&w| w == e[1]), "{}", cycle));
+    }
+
+    #[test]
+    fn tiny_dag() {
+        let g = read_digraph(TINY_DAG.as_bytes()).unwrap();
+
+        let topological = Topological::new(&g);
+        assert!(topological.has_order());
+        assert_eq!(topological.rank(8), Some(0));
+        assert_order(&g, topological.order().unwrap());
+
+        assert_order(&g, &topological_sort(&g).unwrap());
+        assert_order(&g, &kahn(&g).unwrap());
+        assert_eq!(
+            kahn_smallest_first(&g).unwrap(),
+            [2, 0, 1, 3, 5, 8, 7, 6, 4, 9, 10, 11, 12]
+        );
+        assert_eq!(
+            layers(&g).unwrap(),
+            [
+                vec![2, 8],
+                vec![0, 3, 7],
+                vec![1, 5, 6],
+                vec![4, 9],
+                vec![10, 11],
+                vec![12]
+            ]
+        );
+    }
+
+    #[test]
+    fn cyclic() {
+        let g = read_digraph(TINY_DG.as_bytes()).unwrap();
+
+        let topological = Topological::new(&g);
+        assert!(!topological.has_order());
+        assert_eq!(topological.rank(0), None);
+
+        [topological_sort(&g), kahn(&g), kahn_smallest_first(&g)]
+            .iter()
+            .for_each(|result| assert_cycle(&g, result.as_ref().unwrap_err()));
+        assert_cycle(&g, &layers(&g).unwrap_err());
+
+        let g = read_digraph("2 2  0 1  1 0".as_bytes()).unwrap();
+        assert_eq!(kahn(&g).unwrap_err().to_string(), "cycle 0 -> 1 -> 0");
+    }
+
+    #[test]
+    fn random() {
+        let mut rng = thread_rng();
+
+        (0..100).for_each(|_| {
+            let n = rng.gen_range(1..40);
+            let mut g = Digraph::new(n);
+            (0..rng.gen_range(0..2 * n)).for_each(|_| {
+                let (v, w) = (rng.gen_range(0..n), rng.gen_range(0..n));
+                if v < w || rng.gen_bool(0.05) {
+                    g.add_edge(v, w);
+                }
+            });
+
+            match topological_sort(&g) {
+                Ok(order) => {
+                    assert_order(&g, &order);
+                    assert_order(&g, &kahn(&g).unwrap());
+
+                    let smallest = kahn_smallest_first(&g).unwrap();
+                    assert_order(&g, &smallest);
+                    assert!(smallest <= order);
+
+                    let layers = layers(&g).unwrap();
+                    let mut layer_of = vec![0; n];
+                    layers
+                        .iter()
+                        .enumerate()
+                        .for_each(|(i, layer)| layer.iter().for_each(|&v| layer_of[v] = i));
+                    g.vertices().for_each(|v| {
+                        g.adj(v).for_each(|&w| assert!(layer_of[v] < layer_of[w]));
+                    });
+                }
+                Err(cycle) => {
+                    assert_cycle(&g, &cycle);
+                    assert!(kahn(&g).is_err());
+                    assert!(layers(&g).is_err());
+                }
+            }
+        });
+    }
+
+    #[test]
+    fn orders() {
+        let mut g = Digraph::new(5);
+        [(0, 1), (0, 2), (2, 1), (3, 4)]
+            .iter()
+            .for_each(|&(v, w)| g.add_edge(v, w));
+
+        // adjacency is newest first: 0 sees 2 before 1
+        let dfo = DepthFirstOrder::new(&g);
+        assert_eq!(dfo.pre, [0, 2, 1, 3, 4]);
+        assert_eq!(dfo.post, [1, 2, 0, 4, 3]);
+        assert_eq!(dfo.reverse_post(), [3, 4, 0, 2, 1]);
+
+        assert!(dfo.preorder().eq([0, 2, 1, 3, 4]));
+        assert!(dfo.postorder().eq([1, 2, 0, 4, 3]));
+        assert!(dfo.reverse_postorder().eq([3, 4, 0, 2, 1]));
+        assert_eq!((dfo.pre(1), dfo.post(1)), (2, 0));
+        assert_eq!((dfo.pre(3), dfo.post(3)), (3, 4));
+    }
+
+    #[test]
+    fn edge_kinds() {
+        use EdgeKind::*;
+
+        // newest first, so 0 tries 1 before 2 and 1 tries itself before 2
+        let g = read_digraph("4 6  0 2  0 1  1 2  2 0  3 1  1 1".as_bytes()).unwrap();
+        let dfo = DepthFirstOrder::new(&g);
+
+        assert!(dfo.preorder().eq([0, 1, 2, 3]));
+        assert_eq!(
+            dfo.classified_edges(),
+            [
+                (0, 1, Tree),
+                (1, 1, Back),
+                (1, 2, Tree),
+                (2, 0, Back),
+                (0, 2, Forward),
+                (3, 1, Cross)
+            ]
+        );
+        assert!(dfo.back_edges().eq([(1, 1), (2, 0)]));
+    }
+
+    /// The kinds follow from the nesting of the discovery and finishing
+    /// times, and back edges exist exactly when there is a cycle.
+    #[test]
+    fn random_edge_kinds() {
+        let mut rng = thread_rng();
+
+        (0..100).for_each(|_| {
+            let n = rng.gen_range(1..30);
+            let mut g = Digraph::new(n);
+            (0..rng.gen_range(0..2 * n)).for_each(|_| {
+                g.add_edge(rng.gen_range(0..n), rng.gen_range(0..n));
+            });
+
+            let dfo = DepthFirstOrder::new(&g);
+            assert_eq!(dfo.classified_edges().len(), g.num_edges());
+
+            g.vertices().for_each(|v| {
+                assert_eq!(dfo.preorder().nth(dfo.pre(v)), Some(v));
+                assert_eq!(dfo.postorder().nth(dfo.post(v)), Some(v));
+            });
+
+            dfo.classified_edges().iter().for_each(|&(v, w, kind)| {
+                let w_inside_v = dfo.pre(v) < dfo.pre(w) && dfo.post(w) < dfo.post(v);
+                let v_inside_w = dfo.pre(w) <= dfo.pre(v) && dfo.post(v) <= dfo.post(w);
+
+                match kind {
+                    EdgeKind::Tree | EdgeKind::Forward => assert!(w_inside_v),
+                    EdgeKind::Back => assert!(v_inside_w),
+                    EdgeKind::Cross => {
+                        assert!(dfo.pre(w) < dfo.pre(v) && dfo.post(w) < dfo.post(v))
+                    }
+                }
+            });
+
+            assert_eq!(
+                dfo.back_edges().next().is_some(),
+                DirectedCycle::new(&g).cycle.is_some()
+            );
+        });
+    }
+
+    #[test]
+    fn deep_orders() {
+        let n = 1_000_000;
+        let mut g = Digraph::new(n);
+        (1..n).for_each(|v| g.add_edge(v - 1, v));
+
+        let dfo = DepthFirstOrder::new(&g);
+        assert!(dfo.pre.iter().copied().eq(0..n));
+        assert!(dfo.reverse_post().into_iter().eq(0..n));
+
+        g.add_edge(n - 1, 0);
+        assert_eq!(DirectedCycle::new(&g).cycle.unwrap().len(), n + 1);
+        assert!(Topological::new(&g).order.is_none());
+        assert_eq!(topological_sort(&g).unwrap_err().vertices.len(), n + 1);
+    }
+
     #[test]
     fn courses() {
         let sg = create_symbol_digraph("./algs4-data/movies.txt", "/");
 
         let topo = Topological::new(sg.g());
 
-        if let Some(order) = topo.order {
+        if let Some(order) = topo.order() {
             order.iter().for_each(|&v| println!("{}", sg.name(v)));
         }
     }
@@ -0,0 +1,203 @@
+use std::ops::ControlFlow;
+
+use super::{
+    directed_graph::Digraph,
+    search::{depth_first, DfsEvent},
+    strong_connected::{StronglyConnected, TarjanSCC},
+};
+
+/// Fixed size set of small integers, one bit each.
+#[derive(Debug, Clone)]
+struct BitSet {
+    words: Vec<u64>,
+}
+
+impl BitSet {
+    fn new(len: usize) -> Self {
+        BitSet {
+            words: vec![0; len.div_ceil(64)],
+        }
+    }
+
+    fn insert(&mut self, i: usize) {
+        self.words[i / 64] |= 1 << (i % 64);
+    }
+
+    fn contains(&self, i: usize) -> bool {
+        self.words[i / 64] & (1 << (i % 64)) != 0
+    }
+
+    fn union_with(&mut self, other: &BitSet) {
+        self.words
+            .iter_mut()
+            .zip(&other.words)
+            .for_each(|(a, b)| *a |= b);
+    }
+
+    fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words.iter().enumerate().flat_map(|(i, &word)| {
+            (0..64)
+                .filter(move |bit| word & (1 << bit) != 0)
+                .map(move |bit| i * 64 + bit)
+        })
+    }
+}
+
+/// All-pairs reachability: one depth-first search per vertex, each kept as
+/// a row of V bits, so V^2 / 8 bytes.
+#[derive(Debug)]
+pub struct TransitiveClosure {
+    rows: Vec<BitSet>,
+}
+
+impl TransitiveClosure {
+    pub fn new(g: &Digraph) -> Self {
+        let num_vertices = g.num_vertices();
+        let mut marked = vec![false; num_vertices];
+
+        let rows = g
+            .vertices()
+            .map(|s| {
+                let mut row = BitSet::new(num_vertices);
+                marked.iter_mut().for_each(|m| *m = false);
+
+                depth_first(g, s, &mut marked, |event| {
+                    if let DfsEvent::Discover(v) = event {
+                        row.insert(v);
+                    }
+
+                    ControlFlow::Continue(())
+                });
+
+                row
+            })
+            .collect();
+
+        TransitiveClosure { rows }
+    }
+
+    /// is there a directed path from v to w? Every vertex reaches itself.
+    pub fn reachable(&self, v: usize, w: usize) -> bool {
+        self.rows[v].contains(w)
+    }
+
+    /// vertices reachable from v, in increasing order
+    pub fn reachable_from(&self, v: usize) -> impl Iterator<Item = usize> + '_ {
+        self.rows[v].iter()
+    }
+}
+
+/// Reachability on the condensation: vertices of a strong component reach
+/// exactly the same vertices, so one row per component is enough, C^2 / 8
+/// bytes plus the component of every vertex.
+#[derive(Debug)]
+pub struct CompactTransitiveClosure {
+    component: Vec<usize>,
+    rows: Vec<BitSet>,
+}
+
+impl CompactTransitiveClosure {
+    pub fn new(g: &Digraph) -> Self {
+        let (dag, component) = TarjanSCC::new(g).condensation(g);
+        let count = dag.num_vertices();
+
+        // components come in reverse topological order, so every edge c -> d
+        // has d < c and the row of d is complete by the time c needs it
+        let mut rows: Vec<BitSet> = Vec::with_capacity(count);
+        (0..count).for_each(|c| {
+            let mut row = BitSet::new(count);
+            row.insert(c);
+            dag.adj(c).for_each(|&d| row.union_with(&rows[d]));
+            rows.push(row);
+        });
+
+        CompactTransitiveClosure { component, rows }
+    }
+
+    pub fn reachable(&self, v: usize, w: usize) -> bool {
+        self.rows[self.component[v]].contains(self.component[w])
+    }
+
+    /// vertices reachable from v, in increasing order
+    pub fn reachable_from(&self, v: usize) -> impl Iterator<Item = usize> + '_ {
+        let row = &self.rows[self.component[v]];
+
+        (0..self.component.len()).filter(move |&w| row.contains(self.component[w]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{thread_rng, Rng};
+
+    use crate::graph::{
+        directed_graph::read_digraph,
+        search::{DepthFirstSearch, Search},
+        TINY_DG,
+    };
+
+    use super::*;
+
+    #[test]
+    fn tiny_dg() {
+        let g = read_digraph(TINY_DG.as_bytes()).unwrap();
+        let tc = TransitiveClosure::new(&g);
+        let compact = CompactTransitiveClosure::new(&g);
+
+        assert_eq!(tc.reachable_from(1).collect::<Vec<_>>(), [1]);
+        assert_eq!(tc.reachable_from(2).collect::<Vec<_>>(), [0, 1, 2, 3, 4, 5]);
+        assert_eq!(
+            compact.reachable_from(9).collect::<Vec<_>>(),
+            [0, 1, 2, 3, 4, 5, 9, 10, 11, 12]
+        );
+        assert!(tc.reachable(7, 1));
+        assert!(!compact.reachable(1, 7));
+    }
+
+    #[test]
+    fn random() {
+        let mut rng = thread_rng();
+
+        (0..100).for_each(|_| {
+            let n = rng.gen_range(1..150);
+            let mut g = Digraph::new(n);
+            (0..rng.gen_range(0..2 * n)).for_each(|_| {
+                g.add_edge(rng.gen_range(0..n), rng.gen_range(0..n));
+            });
+
+            let tc = TransitiveClosure::new(&g);
+            let compact = CompactTransitiveClosure::new(&g);
+
+            (0..n).for_each(|v| {
+                let dfs = DepthFirstSearch::new(&g, v);
+                let expected: Vec<usize> = (0..n).filter(|&w| dfs.marked(w)).collect();
+
+                assert_eq!(tc.reachable_from(v).collect::<Vec<_>>(), expected);
+                assert_eq!(compact.reachable_from(v).collect::<Vec<_>>(), expected);
+                (0..n).for_each(|w| assert_eq!(compact.reachable(v, w), tc.reachable(v, w)));
+            });
+        });
+    }
+
+    /// Objects are vertices and references are edges: whatever the roots
+    /// can't reach is garbage.
+    #[test]
+    fn mark_and_sweep() {
+        let heap = read_digraph("8 7  0 1  1 2  2 1  3 4  5 3  6 7  7 6".as_bytes()).unwrap();
+        let roots = [0, 5];
+
+        let live = DepthFirstSearch::from_sources(&heap, roots);
+        let garbage: Vec<usize> = heap.vertices().filter(|&v| !live.marked(v)).collect();
+
+        assert_eq!(live.count(), 6);
+        assert_eq!(garbage, [6, 7]);
+
+        let closure = TransitiveClosure::new(&heap);
+        heap.vertices().for_each(|v| {
+            assert_eq!(
+                live.marked(v),
+                roots.iter().any(|&r| closure.reachable(r, v))
+            );
+        });
+    }
+}
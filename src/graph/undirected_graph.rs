@@ -1,22 +1,25 @@
 use std::{
-    collections::VecDeque,
     fmt::{Debug, Display},
     fs::File,
     io::{BufReader, Read},
     path::Path,
 };
 
-use super::parse_graph_params;
+use crate::collections::Bag;
+use crate::input::{read_edges, ParseError};
+
+pub use super::search::{BreadthFirstPaths, DepthFirstPaths, DepthFirstSearch, Paths, Search};
+use super::GraphLike;
 
 pub struct Graph {
-    adj_list: Vec<Vec<usize>>,
+    adj_list: Vec<Bag<usize>>,
     num_edges: usize,
 }
 
 impl Graph {
     pub fn new(num_vertices: usize) -> Self {
         Graph {
-            adj_list: vec![vec![]; num_vertices],
+            adj_list: (0..num_vertices).map(|_| Bag::new()).collect(),
             num_edges: 0,
         }
     }
@@ -30,12 +33,12 @@ impl Graph {
     }
 
     pub fn add_edge(&mut self, v: usize, w: usize) {
-        self.adj_list[v].push(w);
-        self.adj_list[w].push(v);
+        self.adj_list[v].add(w);
+        self.adj_list[w].add(v);
         self.num_edges += 1;
     }
 
-    pub fn adj(&self, v: usize) -> std::slice::Iter<usize> {
+    pub fn adj(&self, v: usize) -> impl Iterator<Item = &usize> {
         self.adj_list[v].iter()
     }
 
@@ -61,6 +64,20 @@ impl Graph {
     }
 }
 
+impl GraphLike for Graph {
+    fn num_vertices(&self) -> usize {
+        self.num_vertices()
+    }
+
+    fn num_edges(&self) -> usize {
+        self.num_edges()
+    }
+
+    fn adj(&self, v: usize) -> impl Iterator<Item = &usize> {
+        self.adj(v)
+    }
+}
+
 impl Debug for Graph {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         writeln!(
@@ -84,185 +101,9 @@ impl Debug for Graph {
     }
 }
 
-pub trait Search {
-    /// find vertices connected to a source vertex s
-    fn new(g: &Graph, s: usize) -> Self;
-
-    /// is v connected to s?
-    fn marked(&self, v: usize) -> bool;
-
-    /// how many vertices are connected to s?
-    fn count(&self) -> usize;
-}
-
-pub struct DepthFirstSearch {
-    marked: Vec<bool>,
-    count: usize,
-}
-
-impl DepthFirstSearch {
-    fn visit(&mut self, g: &Graph, v: usize) {
-        self.marked[v] = true;
-        self.count += 1;
-
-        g.adj(v).for_each(|&w| {
-            if !self.marked[w] {
-                self.visit(&g, w);
-            }
-        });
-    }
-}
-
-impl Search for DepthFirstSearch {
-    fn new(g: &Graph, s: usize) -> Self {
-        let mut dfs = DepthFirstSearch {
-            marked: vec![false; g.num_vertices()],
-            count: 0,
-        };
-
-        dfs.visit(&g, s);
-
-        dfs
-    }
-
-    fn marked(&self, v: usize) -> bool {
-        self.marked[v]
-    }
-
-    fn count(&self) -> usize {
-        self.count
-    }
-}
-
-pub trait Paths {
-    /// find paths in G from source s
-    fn new(g: &Graph, s: usize) -> Self;
-
-    /// path from s to v; null if no such path
-    fn path_to(&self, v: usize) -> Option<Vec<usize>>;
-}
-
-#[derive(Debug)]
-pub struct DepthFirstPaths {
-    marked: Vec<bool>,
-    edge_to: Vec<Option<usize>>,
-    s: usize,
-}
-
-impl DepthFirstPaths {
-    /// Can we implement a non-recursive depth-first visit with a
-    /// stack instead of using the function call stack?
-    fn visit(&mut self, g: &Graph, v: usize) {
-        self.marked[v] = true;
-
-        g.adj(v).for_each(|&w| {
-            if !self.marked[w] {
-                self.edge_to[w] = Some(v);
-                self.visit(g, w);
-            }
-        });
-    }
-}
-
-impl Paths for DepthFirstPaths {
-    fn new(g: &Graph, s: usize) -> Self {
-        let num_vertices = g.num_vertices();
-
-        let mut dfp = DepthFirstPaths {
-            marked: vec![false; num_vertices],
-            edge_to: vec![None; num_vertices],
-            s,
-        };
-
-        dfp.visit(g, s);
-
-        dfp
-    }
-
-    fn path_to(&self, mut v: usize) -> Option<Vec<usize>> {
-        if !self.marked[v] {
-            return None;
-        }
-
-        let mut path = Vec::new();
-
-        while let Some(from) = self.edge_to[v] {
-            path.push(v);
-            v = from;
-        }
-
-        path.push(self.s);
-
-        path.reverse();
-
-        Some(path)
-    }
-}
-
-#[derive(Debug)]
-pub struct BreadthFirstPaths {
-    marked: Vec<bool>,
-    edge_to: Vec<Option<usize>>,
-    s: usize,
-}
-
-impl BreadthFirstPaths {
-    fn visit(&mut self, g: &Graph, v: usize) {
-        self.marked[v] = true;
-        let mut queue = VecDeque::new();
-        queue.push_back(v);
-
-        while !queue.is_empty() {
-            let v = queue.pop_front().unwrap();
-
-            g.adj(v).for_each(|&w| {
-                if !self.marked[w] {
-                    self.edge_to[w] = Some(v);
-                    self.marked[w] = true;
-                    queue.push_back(w);
-                }
-            });
-        }
-    }
-}
-
-impl Paths for BreadthFirstPaths {
-    fn new(g: &Graph, s: usize) -> Self {
-        let num_vertices = g.num_vertices();
-
-        let mut dfp = BreadthFirstPaths {
-            marked: vec![false; num_vertices],
-            edge_to: vec![None; num_vertices],
-            s,
-        };
-
-        dfp.visit(g, s);
-
-        dfp
-    }
-
-    fn path_to(&self, mut v: usize) -> Option<Vec<usize>> {
-        if !self.marked[v] {
-            return None;
-        }
-
-        let mut path = Vec::new();
-
-        while let Some(from) = self.edge_to[v] {
-            path.push(v);
-            v = from;
-        }
-
-        path.push(self.s);
-
-        path.reverse();
-
-        Some(path)
-    }
-}
-
-fn create_graph_from_path<P: AsRef<Path>>(path: P) -> Graph {
-    let (num_vertices, edges) = parse_graph_params(path);
+/// Read a graph in the algs4-data format from any reader.
+pub fn read_graph<R: Read>(reader: R) -> Result<Graph, ParseError> {
+    let (num_vertices, edges) = read_edges(reader)?;
 
     let mut g = Graph::new(num_vertices);
 
@@ -270,7 +111,13 @@ fn create_graph_from_path<P: AsRef<Path>>(path: P) -> Graph {
         g.add_edge(a, b);
     });
 
-    g
+    Ok(g)
+}
+
+pub fn create_graph_from_path<P: AsRef<Path>>(path: P) -> Graph {
+    let file = File::open(path).unwrap();
+
+    read_graph(BufReader::new(file)).unwrap_or_else(|e| panic!("{}", e))
 }
 
 #[cfg(test)]
@@ -281,6 +128,16 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn read() {
+        let g = read_graph("3\r\n2\r\n0\t1\r\n# comment\r\n1 2\r\n".as_bytes()).unwrap();
+        assert_eq!(g.num_vertices(), 3);
+        assert_eq!(g.num_edges(), 2);
+
+        let e = read_graph("3\n2\n0 1\n1 3\n".as_bytes()).unwrap_err();
+        assert_eq!(e.to_string(), "4:3: 3 is out of range for 3 vertices");
+    }
+
     #[test]
     fn graph() {
         let mut g = create_graph_from_path("./algs4-data/tinyG.txt");
@@ -6,7 +6,7 @@ use std::{
     path::Path,
 };
 
-use crate::graph::undirected_graph::Graph;
+use super::{undirected_graph::Graph, GraphLike};
 
 #[derive(Debug)]
 pub struct SymbolGraph {
@@ -65,6 +65,20 @@ impl SymbolGraph {
     }
 }
 
+impl GraphLike for SymbolGraph {
+    fn num_vertices(&self) -> usize {
+        self.g.num_vertices()
+    }
+
+    fn num_edges(&self) -> usize {
+        self.g.num_edges()
+    }
+
+    fn adj(&self, v: usize) -> impl Iterator<Item = &usize> {
+        self.g.adj(v)
+    }
+}
+
 pub fn create_symbol_graph<T: AsRef<Path>>(path: T, delim: &str) -> SymbolGraph {
     let file = File::open(path).unwrap();
 
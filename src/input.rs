@@ -0,0 +1,456 @@
+//! Parsing of the whitespace separated number files of algs4-data, shared by
+//! union-find, graph and flow network loading.
+//!
+//! Tokens may be separated by any whitespace including tabs and CRLF line
+//! endings, blank lines are ignored and `#` starts a comment running to the
+//! end of the line. Lines and columns in errors count from 1.
+
+use std::{
+    error::Error,
+    fmt::Display,
+    io::{self, Read},
+    str::FromStr,
+};
+
+#[derive(Debug)]
+pub enum ParseError {
+    Io(io::Error),
+    /// the input ended while `expected` was still missing
+    UnexpectedEnd {
+        line: usize,
+        column: usize,
+        expected: &'static str,
+    },
+    InvalidNumber {
+        line: usize,
+        column: usize,
+        text: String,
+    },
+    VertexOutOfRange {
+        line: usize,
+        column: usize,
+        vertex: usize,
+        count: usize,
+    },
+    /// the number of items differs from the one declared in the header
+    CountMismatch {
+        line: usize,
+        column: usize,
+        declared: usize,
+        found: usize,
+        /// what was counted, such as "edges"
+        unit: &'static str,
+    },
+    /// a flow network capacity below zero
+    NegativeCapacity {
+        line: usize,
+        column: usize,
+        capacity: f64,
+    },
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseError::Io(e) => write!(f, "{}", e),
+            ParseError::UnexpectedEnd {
+                line,
+                column,
+                expected,
+            } => write!(f, "{}:{}: expected {}", line, column, expected),
+            ParseError::InvalidNumber { line, column, text } => {
+                write!(f, "{}:{}: invalid number '{}'", line, column, text)
+            }
+            ParseError::VertexOutOfRange {
+                line,
+                column,
+                vertex,
+                count,
+            } => write!(
+                f,
+                "{}:{}: {} is out of range for {} vertices",
+                line, column, vertex, count
+            ),
+            ParseError::CountMismatch {
+                line,
+                column,
+                declared,
+                found,
+                unit,
+            } => write!(
+                f,
+                "{}:{}: declared {} {} but found {}",
+                line, column, declared, unit, found
+            ),
+            ParseError::NegativeCapacity {
+                line,
+                column,
+                capacity,
+            } => write!(f, "{}:{}: negative capacity {}", line, column, capacity),
+        }
+    }
+}
+
+impl Error for ParseError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ParseError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for ParseError {
+    fn from(e: io::Error) -> Self {
+        ParseError::Io(e)
+    }
+}
+
+struct Token<'a> {
+    text: &'a str,
+    line: usize,
+    column: usize,
+}
+
+struct Tokens<'a> {
+    tokens: Vec<Token<'a>>,
+    next: usize,
+    /// position just past the last character of the input
+    end: (usize, usize),
+}
+
+impl<'a> Tokens<'a> {
+    fn new(input: &'a str) -> Self {
+        let mut tokens = Vec::new();
+        let mut end = (1, 1);
+
+        input.split('\n').enumerate().for_each(|(i, line)| {
+            let line = line.split('#').next().unwrap();
+            let mut start: Option<(usize, usize)> = None;
+
+            line.char_indices()
+                .chain(std::iter::once((line.len(), ' ')))
+                .enumerate()
+                .for_each(|(column, (offset, c))| match (c.is_whitespace(), start) {
+                    (false, None) => start = Some((column, offset)),
+                    (true, Some((column, from))) => {
+                        tokens.push(Token {
+                            text: &line[from..offset],
+                            line: i + 1,
+                            column: column + 1,
+                        });
+                        start = None;
+                    }
+                    _ => {}
+                });
+
+            end = (i + 1, line.trim_end().chars().count() + 1);
+        });
+
+        Tokens {
+            tokens,
+            next: 0,
+            end,
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.next == self.tokens.len()
+    }
+
+    /// position of the next token, or of the end of input
+    fn position(&self) -> (usize, usize) {
+        self.tokens
+            .get(self.next)
+            .map_or(self.end, |token| (token.line, token.column))
+    }
+
+    fn next_usize(&mut self, expected: &'static str) -> Result<usize, ParseError> {
+        self.next_number(expected)
+    }
+
+    fn next_number<T: FromStr>(&mut self, expected: &'static str) -> Result<T, ParseError> {
+        let token = self
+            .tokens
+            .get(self.next)
+            .ok_or(ParseError::UnexpectedEnd {
+                line: self.end.0,
+                column: self.end.1,
+                expected,
+            })?;
+        self.next += 1;
+
+        token.text.parse().map_err(|_| ParseError::InvalidNumber {
+            line: token.line,
+            column: token.column,
+            text: token.text.to_string(),
+        })
+    }
+
+    fn next_vertex(&mut self, count: usize, expected: &'static str) -> Result<usize, ParseError> {
+        let (line, column) = self.position();
+        let vertex = self.next_usize(expected)?;
+
+        if vertex >= count {
+            return Err(ParseError::VertexOutOfRange {
+                line,
+                column,
+                vertex,
+                count,
+            });
+        }
+
+        Ok(vertex)
+    }
+
+    fn next_pair(&mut self, count: usize) -> Result<(usize, usize), ParseError> {
+        Ok((
+            self.next_vertex(count, "a vertex")?,
+            self.next_vertex(count, "the second vertex of a pair")?,
+        ))
+    }
+
+    fn next_weight(&mut self) -> Result<f64, ParseError> {
+        let (line, column) = self.position();
+        let weight: f64 = self.next_number("a weight")?;
+
+        if !weight.is_finite() {
+            return Err(ParseError::InvalidNumber {
+                line,
+                column,
+                text: self.tokens[self.next - 1].text.to_string(),
+            });
+        }
+
+        Ok(weight)
+    }
+
+    /// a pair followed by a finite weight
+    fn next_weighted(&mut self, count: usize) -> Result<WeightedEdge, ParseError> {
+        let (v, w) = self.next_pair(count)?;
+
+        Ok((v, w, self.next_weight()?))
+    }
+
+    /// exactly `declared` items of `per_item` tokens each, up to the end
+    fn next_items<T>(
+        &mut self,
+        declared: usize,
+        unit: &'static str,
+        per_item: usize,
+        mut next_item: impl FnMut(&mut Self) -> Result<T, ParseError>,
+    ) -> Result<Vec<T>, ParseError> {
+        let mut items = Vec::with_capacity(declared.min(self.tokens.len() / per_item));
+        while !self.is_empty() {
+            if items.len() == declared {
+                let (line, column) = self.position();
+                let remaining = self.tokens.len() - self.next;
+
+                return Err(ParseError::CountMismatch {
+                    line,
+                    column,
+                    declared,
+                    found: declared + remaining.div_ceil(per_item),
+                    unit,
+                });
+            }
+
+            items.push(next_item(self)?);
+        }
+
+        if items.len() < declared {
+            return Err(ParseError::CountMismatch {
+                line: self.end.0,
+                column: self.end.1,
+                declared,
+                found: items.len(),
+                unit,
+            });
+        }
+
+        Ok(items)
+    }
+}
+
+/// Union-find input: the number of sites followed by any number of pairs.
+pub fn read_pairs<R: Read>(mut reader: R) -> Result<(usize, Vec<(usize, usize)>), ParseError> {
+    let mut input = String::new();
+    reader.read_to_string(&mut input)?;
+
+    let mut tokens = Tokens::new(&input);
+    let count = tokens.next_usize("the number of sites")?;
+
+    let mut pairs = Vec::new();
+    while !tokens.is_empty() {
+        pairs.push(tokens.next_pair(count)?);
+    }
+
+    Ok((count, pairs))
+}
+
+/// Graph input: the number of vertices, the number of edges, then exactly
+/// that many edges.
+pub fn read_edges<R: Read>(mut reader: R) -> Result<(usize, Vec<(usize, usize)>), ParseError> {
+    let mut input = String::new();
+    reader.read_to_string(&mut input)?;
+
+    let mut tokens = Tokens::new(&input);
+    let num_vertices = tokens.next_usize("the number of vertices")?;
+    let num_edges = tokens.next_usize("the number of edges")?;
+
+    let edges = tokens.next_items(num_edges, "edges", 2, |tokens| {
+        tokens.next_pair(num_vertices)
+    })?;
+
+    Ok((num_vertices, edges))
+}
+
+/// v, w and the weight or capacity of the edge between them
+pub type WeightedEdge = (usize, usize, f64);
+
+/// Edge-weighted graph input, like `read_edges` with a weight after every
+/// pair. Weights must be finite but may be negative.
+pub fn read_weighted_edges<R: Read>(reader: R) -> Result<(usize, Vec<WeightedEdge>), ParseError> {
+    read_weighted(reader, |tokens, count| tokens.next_weighted(count))
+}
+
+/// Flow network input, like `read_weighted_edges` but rejecting negative
+/// capacities.
+pub fn read_capacities<R: Read>(reader: R) -> Result<(usize, Vec<WeightedEdge>), ParseError> {
+    read_weighted(reader, |tokens, count| {
+        let (v, w) = tokens.next_pair(count)?;
+        let (line, column) = tokens.position();
+        let capacity = tokens.next_weight()?;
+
+        if capacity < 0.0 {
+            return Err(ParseError::NegativeCapacity {
+                line,
+                column,
+                capacity,
+            });
+        }
+
+        Ok((v, w, capacity))
+    })
+}
+
+fn read_weighted<R: Read>(
+    mut reader: R,
+    mut next_edge: impl FnMut(&mut Tokens, usize) -> Result<WeightedEdge, ParseError>,
+) -> Result<(usize, Vec<WeightedEdge>), ParseError> {
+    let mut input = String::new();
+    reader.read_to_string(&mut input)?;
+
+    let mut tokens = Tokens::new(&input);
+    let num_vertices = tokens.next_usize("the number of vertices")?;
+    let num_edges = tokens.next_usize("the number of edges")?;
+
+    let edges = tokens.next_items(num_edges, "edges", 3, |tokens| {
+        next_edge(tokens, num_vertices)
+    })?;
+
+    Ok((num_vertices, edges))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pairs() {
+        let input = "# tinyUF\r\n3\r\n\r\n0 1\t# first\n 2\t1\n\n";
+        let (count, pairs) = read_pairs(input.as_bytes()).unwrap();
+
+        assert_eq!(count, 3);
+        assert_eq!(pairs, [(0, 1), (2, 1)]);
+    }
+
+    #[test]
+    fn edges() {
+        let (num_vertices, edges) = read_edges("4\n2\n0 1\n3 2\n".as_bytes()).unwrap();
+        assert_eq!(num_vertices, 4);
+        assert_eq!(edges, [(0, 1), (3, 2)]);
+
+        let (_, edges) = read_edges("4 0".as_bytes()).unwrap();
+        assert!(edges.is_empty());
+    }
+
+    #[test]
+    fn weighted_edges() {
+        let (num_vertices, edges) =
+            read_weighted_edges("3\n2\n0 1 2.5\n2 1 3\n".as_bytes()).unwrap();
+        assert_eq!(num_vertices, 3);
+        assert_eq!(edges, [(0, 1, 2.5), (2, 1, 3.0)]);
+
+        let error = |input: &str| {
+            read_weighted_edges(input.as_bytes())
+                .unwrap_err()
+                .to_string()
+        };
+        assert_eq!(error("3 1\n0 1"), "2:4: expected a weight");
+        assert_eq!(error("3 1\n0 1 NaN"), "2:5: invalid number 'NaN'");
+        assert_eq!(
+            error("3 1\n0 1 1.0\n1 2 1.0"),
+            "3:1: declared 1 edges but found 2"
+        );
+
+        let (_, edges) = read_weighted_edges("2 1  0 1 -2.0".as_bytes()).unwrap();
+        assert_eq!(edges, [(0, 1, -2.0)]);
+    }
+
+    #[test]
+    fn capacities() {
+        let (_, edges) = read_capacities("3 2  0 1 2.0  1 2 0".as_bytes()).unwrap();
+        assert_eq!(edges, [(0, 1, 2.0), (1, 2, 0.0)]);
+
+        assert_eq!(
+            read_capacities("3 1\n0 1 -2.0".as_bytes())
+                .unwrap_err()
+                .to_string(),
+            "2:5: negative capacity -2"
+        );
+    }
+
+    fn error(result: Result<(usize, Vec<(usize, usize)>), ParseError>) -> String {
+        result.unwrap_err().to_string()
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            error(read_pairs("".as_bytes())),
+            "1:1: expected the number of sites"
+        );
+        assert_eq!(
+            error(read_pairs("3\n0 x1".as_bytes())),
+            "2:3: invalid number 'x1'"
+        );
+        assert_eq!(
+            error(read_pairs("3\n0 1\n\t2 3".as_bytes())),
+            "3:4: 3 is out of range for 3 vertices"
+        );
+        assert_eq!(
+            error(read_pairs("3\n0 1\n2  ".as_bytes())),
+            "3:2: expected the second vertex of a pair"
+        );
+        assert_eq!(
+            error(read_edges("3\n1\n0 1\n1 2\n".as_bytes())),
+            "4:1: declared 1 edges but found 2"
+        );
+        assert_eq!(
+            error(read_edges("3\n3\n0 1\n1 2 # missing one".as_bytes())),
+            "4:4: declared 3 edges but found 2"
+        );
+        assert_eq!(
+            error(read_edges("3 -1".as_bytes())),
+            "1:3: invalid number '-1'"
+        );
+    }
+
+    #[test]
+    fn io_error() {
+        let e = read_pairs(&[0xff, 0xfe][..]).unwrap_err();
+        assert!(matches!(e, ParseError::Io(_)));
+        assert!(e.source().is_some());
+    }
+}
@@ -1,8 +1,12 @@
 #![allow(unused)]
 
 pub mod binary_search;
+pub mod collections;
+pub mod doubling;
 pub mod graph;
+pub mod input;
 pub mod misc;
+pub mod persistent_list;
 pub mod singly_linked_list;
 pub mod sort;
 pub mod symbol_table;
@@ -1,13 +1,11 @@
-use std::collections::HashMap;
-
-use crate::binary_search::binary_search;
+use std::{collections::HashMap, error::Error, fmt::Display};
 
 pub fn two_sum(list: &[i32]) -> usize {
     let mut count = 0;
 
     for i in 0..list.len() {
         for j in (i + 1)..list.len() {
-            if list[i] + list[j] == 0 {
+            if list[i] as i64 + list[j] as i64 == 0 {
                 count += 1;
             }
         }
@@ -16,22 +14,52 @@ pub fn two_sum(list: &[i32]) -> usize {
     count
 }
 
+/// number of items in the sorted `list[from..]` equal to `target`
+fn count_equal(list: &[i32], from: usize, target: i64) -> usize {
+    let list = &list[from.min(list.len())..];
+    let low = list.partition_point(|&x| (x as i64) < target);
+    let high = list.partition_point(|&x| (x as i64) <= target);
+
+    high - low
+}
+
 pub fn two_sum_fast(list: &mut [i32]) -> usize {
     list.sort();
 
     let mut count = 0;
 
-    list.iter().enumerate().for_each(|(i, item)| {
-        if let Ok(j) = list.binary_search(&-item) {
-            if i < j {
-                count += 1;
-            }
-        }
+    list.iter().enumerate().for_each(|(i, &item)| {
+        count += count_equal(list, i + 1, -(item as i64));
+    });
+
+    count
+}
+
+/// linear time, counting each value seen so far
+pub fn two_sum_hash(list: &[i32]) -> usize {
+    let mut seen: HashMap<i64, usize> = HashMap::new();
+    let mut count = 0;
+
+    list.iter().for_each(|&item| {
+        count += seen.get(&-(item as i64)).unwrap_or(&0);
+        *seen.entry(item as i64).or_insert(0) += 1;
     });
 
     count
 }
 
+/// index pairs (i, j) with i < j and list[i] + list[j] == 0
+pub fn two_sum_pairs(list: &[i32]) -> impl Iterator<Item = (usize, usize)> + '_ {
+    let mut indices: HashMap<i64, Vec<usize>> = HashMap::new();
+
+    list.iter().enumerate().flat_map(move |(j, &item)| {
+        let pairs = indices.get(&-(item as i64)).cloned().unwrap_or_default();
+        indices.entry(item as i64).or_default().push(j);
+
+        pairs.into_iter().map(move |i| (i, j))
+    })
+}
+
 pub fn three_sum(list: &[i32]) -> usize {
     let mut count = 0;
     let len = list.len();
@@ -39,7 +67,7 @@ pub fn three_sum(list: &[i32]) -> usize {
     for i in 0..len {
         for j in (i + 1)..len {
             for k in (j + 1)..len {
-                if list[i] + list[j] + list[k] == 0 {
+                if list[i] as i64 + list[j] as i64 + list[k] as i64 == 0 {
                     count += 1;
                 }
             }
@@ -57,10 +85,53 @@ pub fn three_sum_binary(list: &mut [i32]) -> usize {
 
     for i in 0..len {
         for j in (i + 1)..len {
-            if let Ok(k) = list.binary_search(&(-list[i] - list[j])) {
-                if j < k {
-                    count += 1;
+            count += count_equal(list, j + 1, -(list[i] as i64) - list[j] as i64);
+        }
+    }
+
+    count
+}
+
+/// N^2: for every i, walk two pointers inward over list[i + 1..]
+pub fn three_sum_fast(list: &mut [i32]) -> usize {
+    list.sort();
+
+    let mut count = 0;
+    let len = list.len();
+
+    for i in 0..len {
+        let target = -(list[i] as i64);
+        let mut low = i + 1;
+        let mut high = len - 1;
+
+        while low < high {
+            let sum = list[low] as i64 + list[high] as i64;
+
+            if sum < target {
+                low += 1;
+            } else if sum > target {
+                high -= 1;
+            } else if list[low] == list[high] {
+                // every pair in list[low..=high] matches
+                let n = high - low + 1;
+                count += n * (n - 1) / 2;
+                break;
+            } else {
+                let (low_value, high_value) = (list[low], list[high]);
+                let mut low_count = 0;
+                let mut high_count = 0;
+
+                while list[low] == low_value {
+                    low += 1;
+                    low_count += 1;
+                }
+
+                while list[high] == high_value {
+                    high -= 1;
+                    high_count += 1;
                 }
+
+                count += low_count * high_count;
             }
         }
     }
@@ -68,18 +139,89 @@ pub fn three_sum_binary(list: &mut [i32]) -> usize {
     count
 }
 
-enum BinaryOperators {
+/// index triples (i, j, k) with i < j < k and list[i] + list[j] + list[k] == 0
+pub fn three_sum_triples(list: &[i32]) -> impl Iterator<Item = (usize, usize, usize)> + '_ {
+    let mut indices: HashMap<i64, Vec<usize>> = HashMap::new();
+
+    list.iter().enumerate().for_each(|(k, &item)| {
+        indices.entry(item as i64).or_default().push(k);
+    });
+
+    let len = list.len();
+
+    (0..len)
+        .flat_map(move |i| ((i + 1)..len).map(move |j| (i, j)))
+        .flat_map(move |(i, j)| {
+            let target = -(list[i] as i64) - list[j] as i64;
+
+            indices
+                .get(&target)
+                .map(|ks| {
+                    let from = ks.partition_point(|&k| k <= j);
+                    ks[from..].iter().map(|&k| (i, j, k)).collect::<Vec<_>>()
+                })
+                .unwrap_or_default()
+        })
+}
+
+/// distinct value triples a <= b <= c with a + b + c == 0, in ascending order
+pub fn three_sum_values(list: &mut [i32]) -> impl Iterator<Item = (i32, i32, i32)> {
+    list.sort();
+
+    let mut result = vec![];
+    let len = list.len();
+
+    for i in 0..len {
+        if i > 0 && list[i] == list[i - 1] {
+            continue;
+        }
+
+        let target = -(list[i] as i64);
+        let mut low = i + 1;
+        let mut high = len - 1;
+
+        while low < high {
+            let sum = list[low] as i64 + list[high] as i64;
+
+            if sum < target {
+                low += 1;
+            } else if sum > target {
+                high -= 1;
+            } else {
+                result.push((list[i], list[low], list[high]));
+
+                let low_value = list[low];
+                while low < high && list[low] == low_value {
+                    low += 1;
+                }
+            }
+        }
+    }
+
+    result.into_iter()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BinaryOperators {
     Add,
     Subtract,
     Multiply,
     Divide,
+    Power,
 }
 
-enum UnaryOperators {
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum UnaryOperators {
     Sqrt,
+    Negate,
+    Ln,
+    Sin,
+    Cos,
+    Abs,
 }
 
-enum Operators {
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Operators {
     Unary(UnaryOperators),
     Binary(BinaryOperators),
 }
@@ -88,6 +230,23 @@ impl UnaryOperators {
     fn evaluate(&self, operand: f64) -> f64 {
         match self {
             UnaryOperators::Sqrt => operand.sqrt(),
+            UnaryOperators::Negate => -operand,
+            UnaryOperators::Ln => operand.ln(),
+            UnaryOperators::Sin => operand.sin(),
+            UnaryOperators::Cos => operand.cos(),
+            UnaryOperators::Abs => operand.abs(),
+        }
+    }
+
+    /// function name, `neg` for unary minus in postfix
+    pub fn name(&self) -> &'static str {
+        match self {
+            UnaryOperators::Sqrt => "sqrt",
+            UnaryOperators::Negate => "neg",
+            UnaryOperators::Ln => "ln",
+            UnaryOperators::Sin => "sin",
+            UnaryOperators::Cos => "cos",
+            UnaryOperators::Abs => "abs",
         }
     }
 }
@@ -99,60 +258,991 @@ impl BinaryOperators {
             BinaryOperators::Subtract => l - r,
             BinaryOperators::Multiply => l * r,
             BinaryOperators::Divide => l / r,
+            BinaryOperators::Power => l.powf(r),
+        }
+    }
+
+    pub fn symbol(&self) -> char {
+        match self {
+            BinaryOperators::Add => '+',
+            BinaryOperators::Subtract => '-',
+            BinaryOperators::Multiply => '*',
+            BinaryOperators::Divide => '/',
+            BinaryOperators::Power => '^',
+        }
+    }
+
+    fn from_symbol(c: char) -> Option<Self> {
+        match c {
+            '+' => Some(BinaryOperators::Add),
+            '-' => Some(BinaryOperators::Subtract),
+            '*' => Some(BinaryOperators::Multiply),
+            '/' => Some(BinaryOperators::Divide),
+            '^' => Some(BinaryOperators::Power),
+            _ => None,
         }
     }
 }
 
-pub fn evaluate_arithmetic_expression(expression: &str) -> f64 {
-    let mut operators = Vec::new();
-    let mut operands: Vec<f64> = Vec::new();
+impl Operators {
+    /// functions callable as `name(args)`
+    fn function(name: &str) -> Option<Self> {
+        match name {
+            "sqrt" => Some(Operators::Unary(UnaryOperators::Sqrt)),
+            "ln" => Some(Operators::Unary(UnaryOperators::Ln)),
+            "sin" => Some(Operators::Unary(UnaryOperators::Sin)),
+            "cos" => Some(Operators::Unary(UnaryOperators::Cos)),
+            "abs" => Some(Operators::Unary(UnaryOperators::Abs)),
+            "pow" => Some(Operators::Binary(BinaryOperators::Power)),
+            _ => None,
+        }
+    }
+
+    fn arity(&self) -> usize {
+        match self {
+            Operators::Unary(_) => 1,
+            Operators::Binary(_) => 2,
+        }
+    }
+
+    /// binding strength of infix and prefix operators; functions are
+    /// delimited by their parentheses so never compete
+    fn precedence(&self) -> u8 {
+        match self {
+            Operators::Binary(BinaryOperators::Add | BinaryOperators::Subtract) => 1,
+            Operators::Binary(BinaryOperators::Multiply | BinaryOperators::Divide) => 2,
+            Operators::Unary(UnaryOperators::Negate) => 3,
+            Operators::Binary(BinaryOperators::Power) => 4,
+            Operators::Unary(_) => 5,
+        }
+    }
 
-    expression.split(' ').for_each(|s| match s {
-        "+" => operators.push(Operators::Binary(BinaryOperators::Add)),
-        "-" => operators.push(Operators::Binary(BinaryOperators::Subtract)),
-        "*" => operators.push(Operators::Binary(BinaryOperators::Multiply)),
-        "/" => operators.push(Operators::Binary(BinaryOperators::Divide)),
-        "sqrt" => operators.push(Operators::Unary(UnaryOperators::Sqrt)),
-        "(" => {}
-        ")" => {
-            if let Some(right) = operands.pop() {
-                if let Some(op) = operators.pop() {
-                    match op {
-                        Operators::Unary(op) => {
-                            operands.push(op.evaluate(right));
+    fn right_associative(&self) -> bool {
+        matches!(
+            self,
+            Operators::Binary(BinaryOperators::Power) | Operators::Unary(_)
+        )
+    }
+
+    fn apply(&self, operands: &[f64]) -> f64 {
+        match self {
+            Operators::Unary(op) => op.evaluate(operands[0]),
+            Operators::Binary(op) => op.evaluate(operands[0], operands[1]),
+        }
+    }
+}
+
+/// `evaluate_expression` with no variables bound.
+pub fn evaluate_arithmetic_expression(expression: &str) -> Result<f64, EvalError> {
+    evaluate_expression(expression, &HashMap::new())
+}
+
+/// Errors carry the byte offset into the expression where they were detected.
+#[derive(Debug, Clone, PartialEq)]
+pub enum EvalError {
+    UnexpectedCharacter {
+        position: usize,
+        found: char,
+    },
+    InvalidNumber {
+        position: usize,
+        text: String,
+    },
+    UnexpectedToken {
+        position: usize,
+    },
+    UnexpectedEnd,
+    UnmatchedParenthesis {
+        position: usize,
+    },
+    UnknownFunction {
+        position: usize,
+        name: String,
+    },
+    UnknownVariable {
+        position: usize,
+        name: String,
+    },
+    WrongArgumentCount {
+        position: usize,
+        name: String,
+        expected: usize,
+        found: usize,
+    },
+}
+
+impl Display for EvalError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EvalError::UnexpectedCharacter { position, found } => {
+                write!(f, "unexpected character '{}' at {}", found, position)
+            }
+            EvalError::InvalidNumber { position, text } => {
+                write!(f, "invalid number '{}' at {}", text, position)
+            }
+            EvalError::UnexpectedToken { position } => {
+                write!(f, "unexpected token at {}", position)
+            }
+            EvalError::UnexpectedEnd => write!(f, "unexpected end of expression"),
+            EvalError::UnmatchedParenthesis { position } => {
+                write!(f, "unmatched parenthesis at {}", position)
+            }
+            EvalError::UnknownFunction { position, name } => {
+                write!(f, "unknown function '{}' at {}", name, position)
+            }
+            EvalError::UnknownVariable { position, name } => {
+                write!(f, "unknown variable '{}' at {}", name, position)
+            }
+            EvalError::WrongArgumentCount {
+                position,
+                name,
+                expected,
+                found,
+            } => write!(
+                f,
+                "'{}' at {} takes {} argument(s) but {} were given",
+                name, position, expected, found
+            ),
+        }
+    }
+}
+
+impl Error for EvalError {}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Identifier(String),
+    Symbol(char),
+    LeftParen,
+    RightParen,
+    Comma,
+}
+
+/// split an expression into (token, position) pairs; whitespace is optional
+fn tokenize(expression: &str) -> Result<Vec<(Token, usize)>, EvalError> {
+    let mut tokens = vec![];
+    let mut chars = expression.char_indices().peekable();
+
+    while let Some(&(position, c)) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c.is_ascii_digit() || c == '.' {
+            let mut end = position;
+            let mut prev = c;
+
+            while let Some(&(i, c)) = chars.peek() {
+                let exponent_sign = (c == '+' || c == '-') && (prev == 'e' || prev == 'E');
+
+                if c.is_ascii_digit() || c == '.' || c == 'e' || c == 'E' || exponent_sign {
+                    end = i + c.len_utf8();
+                    prev = c;
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+
+            let text = &expression[position..end];
+
+            match text.parse() {
+                Ok(num) => tokens.push((Token::Number(num), position)),
+                Err(_) => {
+                    return Err(EvalError::InvalidNumber {
+                        position,
+                        text: text.to_string(),
+                    })
+                }
+            }
+        } else if c.is_alphabetic() || c == '_' {
+            let mut end = position;
+
+            while let Some(&(i, c)) = chars.peek() {
+                if c.is_alphanumeric() || c == '_' {
+                    end = i + c.len_utf8();
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+
+            tokens.push((
+                Token::Identifier(expression[position..end].to_string()),
+                position,
+            ));
+        } else {
+            let token = match c {
+                '(' => Token::LeftParen,
+                ')' => Token::RightParen,
+                ',' => Token::Comma,
+                '+' | '-' | '*' | '/' | '^' => Token::Symbol(c),
+                found => return Err(EvalError::UnexpectedCharacter { position, found }),
+            };
+
+            tokens.push((token, position));
+            chars.next();
+        }
+    }
+
+    Ok(tokens)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum PostfixItem {
+    Number(f64),
+    Variable(String),
+    Operator(Operators),
+}
+
+enum StackItem {
+    Operator(Operators),
+    /// an open parenthesis, possibly the argument list of a function
+    Paren {
+        position: usize,
+        function: Option<(Operators, String)>,
+        args: usize,
+    },
+}
+
+/// Dijkstra's shunting-yard: reorder infix tokens into postfix, keeping the
+/// position of every item for error reporting.
+fn to_postfix(tokens: Vec<(Token, usize)>) -> Result<Vec<(PostfixItem, usize)>, EvalError> {
+    let mut output = vec![];
+    let mut stack: Vec<(StackItem, usize)> = vec![];
+    // true where an operand (or prefix operator) is allowed
+    let mut expect_operand = true;
+    let mut tokens = tokens.into_iter().peekable();
+
+    while let Some((token, position)) = tokens.next() {
+        match token {
+            Token::Number(_) | Token::Identifier(_) if !expect_operand => {
+                return Err(EvalError::UnexpectedToken { position });
+            }
+            Token::Number(num) => {
+                output.push((PostfixItem::Number(num), position));
+                expect_operand = false;
+            }
+            Token::Identifier(name) => {
+                if matches!(tokens.peek(), Some((Token::LeftParen, _))) {
+                    let function =
+                        Operators::function(&name).ok_or_else(|| EvalError::UnknownFunction {
+                            position,
+                            name: name.clone(),
+                        })?;
+
+                    tokens.next();
+
+                    stack.push((
+                        StackItem::Paren {
+                            position,
+                            function: Some((function, name)),
+                            args: 1,
+                        },
+                        position,
+                    ));
+                } else {
+                    output.push((PostfixItem::Variable(name), position));
+                    expect_operand = false;
+                }
+            }
+            Token::Symbol(c) if expect_operand => match c {
+                '-' => stack.push((
+                    StackItem::Operator(Operators::Unary(UnaryOperators::Negate)),
+                    position,
+                )),
+                '+' => {}
+                _ => return Err(EvalError::UnexpectedToken { position }),
+            },
+            Token::Symbol(c) => {
+                let op = Operators::Binary(BinaryOperators::from_symbol(c).unwrap());
+
+                while let Some((StackItem::Operator(top), _)) = stack.last() {
+                    if top.precedence() > op.precedence()
+                        || (top.precedence() == op.precedence() && !op.right_associative())
+                    {
+                        output.push((PostfixItem::Operator(*top), stack.pop().unwrap().1));
+                    } else {
+                        break;
+                    }
+                }
+
+                stack.push((StackItem::Operator(op), position));
+                expect_operand = true;
+            }
+            Token::LeftParen if !expect_operand => {
+                return Err(EvalError::UnexpectedToken { position });
+            }
+            Token::LeftParen => stack.push((
+                StackItem::Paren {
+                    position,
+                    function: None,
+                    args: 1,
+                },
+                position,
+            )),
+            Token::Comma | Token::RightParen if expect_operand => {
+                return Err(EvalError::UnexpectedToken { position });
+            }
+            Token::Comma | Token::RightParen => {
+                loop {
+                    match stack.last_mut() {
+                        Some((StackItem::Operator(op), _)) => {
+                            let op = *op;
+                            output.push((PostfixItem::Operator(op), stack.pop().unwrap().1));
                         }
-                        Operators::Binary(op) => {
-                            if let Some(left) = operands.pop() {
-                                operands.push(op.evaluate(left, right));
+                        Some((StackItem::Paren { args, .. }, _)) => {
+                            if token == Token::Comma {
+                                *args += 1;
                             }
+                            break;
                         }
+                        None => return Err(EvalError::UnmatchedParenthesis { position }),
                     }
                 }
+
+                if token == Token::Comma {
+                    expect_operand = true;
+                    continue;
+                }
+
+                if let Some((StackItem::Paren { function, args, .. }, start)) = stack.pop() {
+                    match function {
+                        Some((op, name)) if op.arity() != args => {
+                            return Err(EvalError::WrongArgumentCount {
+                                position: start,
+                                name,
+                                expected: op.arity(),
+                                found: args,
+                            });
+                        }
+                        Some((op, _)) => output.push((PostfixItem::Operator(op), start)),
+                        None if args > 1 => {
+                            return Err(EvalError::UnexpectedToken { position: start });
+                        }
+                        None => {}
+                    }
+                }
+
+                expect_operand = false;
             }
         }
-        other => {
-            if let Ok(num) = other.parse() {
-                operands.push(num);
+    }
+
+    if expect_operand {
+        return Err(EvalError::UnexpectedEnd);
+    }
+
+    while let Some((item, position)) = stack.pop() {
+        match item {
+            StackItem::Operator(op) => output.push((PostfixItem::Operator(op), position)),
+            StackItem::Paren { position, .. } => {
+                return Err(EvalError::UnmatchedParenthesis { position });
             }
         }
-    });
+    }
+
+    Ok(output)
+}
+
+/// Run the postfix stack machine: `operand` turns a number or variable into a
+/// value, `apply` combines an operator with its arguments in order.
+fn fold_postfix<T>(
+    items: &[(PostfixItem, usize)],
+    mut operand: impl FnMut(&PostfixItem, usize) -> Result<T, EvalError>,
+    mut apply: impl FnMut(Operators, Vec<T>) -> T,
+) -> Result<T, EvalError> {
+    let mut stack: Vec<(T, usize)> = vec![];
+
+    for (item, position) in items {
+        let position = *position;
+
+        match item {
+            PostfixItem::Operator(op) => {
+                if stack.len() < op.arity() {
+                    return Err(EvalError::UnexpectedToken { position });
+                }
+
+                let args = stack.split_off(stack.len() - op.arity());
+                // a subexpression is located where its first operand starts
+                let start = args[0].1;
+
+                stack.push((
+                    apply(*op, args.into_iter().map(|(arg, _)| arg).collect()),
+                    start,
+                ));
+            }
+            other => stack.push((operand(other, position)?, position)),
+        }
+    }
+
+    // anything below the top is an operand no operator consumed
+    match stack.len() {
+        0 => Err(EvalError::UnexpectedEnd),
+        1 => Ok(stack.pop().unwrap().0),
+        _ => Err(EvalError::UnexpectedToken {
+            position: stack[1].1,
+        }),
+    }
+}
+
+fn evaluate_postfix_items(
+    items: &[(PostfixItem, usize)],
+    variables: &HashMap<String, f64>,
+) -> Result<f64, EvalError> {
+    fold_postfix(
+        items,
+        |item, position| match item {
+            PostfixItem::Number(num) => Ok(*num),
+            PostfixItem::Variable(name) => {
+                variables
+                    .get(name)
+                    .copied()
+                    .ok_or_else(|| EvalError::UnknownVariable {
+                        position,
+                        name: name.clone(),
+                    })
+            }
+            PostfixItem::Operator(_) => unreachable!(),
+        },
+        |op, args| op.apply(&args),
+    )
+}
+
+/// Evaluate an infix expression such as `2 * x^2 - sqrt(y)`, with the usual
+/// precedence, right-associative `^`, unary minus, the functions sqrt, ln,
+/// sin, cos, abs and pow, and variables looked up in `variables`.
+pub fn evaluate_expression(
+    expression: &str,
+    variables: &HashMap<String, f64>,
+) -> Result<f64, EvalError> {
+    let postfix = to_postfix(tokenize(expression)?)?;
+
+    evaluate_postfix_items(&postfix, variables)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    Number(f64),
+    Variable(String),
+    Unary(UnaryOperators, Box<Expr>),
+    Binary(BinaryOperators, Box<Expr>, Box<Expr>),
+}
+
+impl Expr {
+    /// None if some variable is not bound in `variables`
+    pub fn evaluate(&self, variables: &HashMap<String, f64>) -> Option<f64> {
+        match self {
+            Expr::Number(num) => Some(*num),
+            Expr::Variable(name) => variables.get(name).copied(),
+            Expr::Unary(op, operand) => Some(op.evaluate(operand.evaluate(variables)?)),
+            Expr::Binary(op, l, r) => {
+                Some(op.evaluate(l.evaluate(variables)?, r.evaluate(variables)?))
+            }
+        }
+    }
+
+    /// space-separated reverse Polish notation, e.g. `1 2 3 * +`
+    pub fn to_postfix(&self) -> String {
+        let mut tokens = vec![];
+        self.push_postfix(&mut tokens);
+
+        tokens.join(" ")
+    }
+
+    fn push_postfix(&self, tokens: &mut Vec<String>) {
+        match self {
+            Expr::Number(num) => tokens.push(num.to_string()),
+            Expr::Variable(name) => tokens.push(name.clone()),
+            Expr::Unary(op, operand) => {
+                operand.push_postfix(tokens);
+                tokens.push(op.name().to_string());
+            }
+            Expr::Binary(op, l, r) => {
+                l.push_postfix(tokens);
+                r.push_postfix(tokens);
+                tokens.push(op.symbol().to_string());
+            }
+        }
+    }
+
+    /// precedence this node prints with; a negative literal reads as unary minus
+    fn precedence(&self) -> u8 {
+        match self {
+            Expr::Number(num) if num.is_sign_negative() => {
+                Operators::Unary(UnaryOperators::Negate).precedence()
+            }
+            Expr::Number(_) | Expr::Variable(_) => u8::MAX,
+            Expr::Unary(op, _) => Operators::Unary(*op).precedence(),
+            Expr::Binary(op, _, _) => Operators::Binary(*op).precedence(),
+        }
+    }
+
+    fn is_prefix(&self) -> bool {
+        match self {
+            Expr::Number(num) => num.is_sign_negative(),
+            Expr::Unary(op, _) => *op == UnaryOperators::Negate,
+            _ => false,
+        }
+    }
+
+    fn fmt_operand(&self, f: &mut std::fmt::Formatter<'_>, parenthesize: bool) -> std::fmt::Result {
+        if parenthesize {
+            write!(f, "({})", self)
+        } else {
+            write!(f, "{}", self)
+        }
+    }
+}
+
+/// Infix with only the parentheses needed to parse back to the same tree,
+/// except for negative numbers: `Number(-2.0)` prints as `-2`, which parses
+/// as the negation of `Number(2.0)`, so the value round-trips but the tree
+/// does not.
+impl Display for Expr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Expr::Number(num) => write!(f, "{}", num),
+            Expr::Variable(name) => write!(f, "{}", name),
+            Expr::Unary(UnaryOperators::Negate, operand) => {
+                write!(f, "-")?;
+                operand.fmt_operand(f, operand.precedence() < self.precedence())
+            }
+            Expr::Unary(op, operand) => write!(f, "{}({})", op.name(), operand),
+            Expr::Binary(op, l, r) => {
+                let precedence = self.precedence();
+                let right_associative = Operators::Binary(*op).right_associative();
+
+                l.fmt_operand(
+                    f,
+                    l.precedence() < precedence
+                        || (l.precedence() == precedence && right_associative),
+                )?;
+
+                write!(f, " {} ", op.symbol())?;
+
+                // a prefix operator after an infix one is never ambiguous
+                r.fmt_operand(
+                    f,
+                    !r.is_prefix()
+                        && (r.precedence() < precedence
+                            || (r.precedence() == precedence && !right_associative)),
+                )
+            }
+        }
+    }
+}
+
+/// split reverse Polish notation on whitespace into (token, position) pairs
+fn tokenize_postfix(expression: &str) -> Result<Vec<(PostfixItem, usize)>, EvalError> {
+    let mut items = vec![];
+    let mut start = None;
 
-    operands[0]
+    for (i, c) in expression
+        .char_indices()
+        .chain(std::iter::once((expression.len(), ' ')))
+    {
+        match (start, c.is_whitespace()) {
+            (None, false) => start = Some(i),
+            (Some(position), true) => {
+                items.push((postfix_item(&expression[position..i], position)?, position));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+
+    Ok(items)
+}
+
+fn postfix_item(word: &str, position: usize) -> Result<PostfixItem, EvalError> {
+    let first = word.chars().next().unwrap();
+
+    if word.len() == 1 {
+        if let Some(op) = BinaryOperators::from_symbol(first) {
+            return Ok(PostfixItem::Operator(Operators::Binary(op)));
+        }
+    }
+
+    if first.is_ascii_digit() || first == '.' || first == '-' || first == '+' {
+        return word
+            .parse()
+            .map(PostfixItem::Number)
+            .map_err(|_| EvalError::InvalidNumber {
+                position,
+                text: word.to_string(),
+            });
+    }
+
+    if let Some((i, found)) = word
+        .char_indices()
+        .find(|&(_, c)| !(c.is_alphanumeric() || c == '_'))
+    {
+        return Err(EvalError::UnexpectedCharacter {
+            position: position + i,
+            found,
+        });
+    }
+
+    Ok(match word {
+        "neg" => PostfixItem::Operator(Operators::Unary(UnaryOperators::Negate)),
+        name => Operators::function(name)
+            .map(PostfixItem::Operator)
+            .unwrap_or_else(|| PostfixItem::Variable(name.to_string())),
+    })
+}
+
+fn build_tree(items: &[(PostfixItem, usize)]) -> Result<Expr, EvalError> {
+    fold_postfix(
+        items,
+        |item, _| match item {
+            PostfixItem::Number(num) => Ok(Expr::Number(*num)),
+            PostfixItem::Variable(name) => Ok(Expr::Variable(name.clone())),
+            PostfixItem::Operator(_) => unreachable!(),
+        },
+        |op, mut args| match op {
+            Operators::Unary(op) => Expr::Unary(op, Box::new(args.pop().unwrap())),
+            Operators::Binary(op) => {
+                let r = args.pop().unwrap();
+                let l = args.pop().unwrap();
+                Expr::Binary(op, Box::new(l), Box::new(r))
+            }
+        },
+    )
+}
+
+pub fn parse_infix(expression: &str) -> Result<Expr, EvalError> {
+    build_tree(&to_postfix(tokenize(expression)?)?)
+}
+
+/// Parse space-separated reverse Polish notation. Operators are `+ - * / ^`,
+/// the function names, and `neg` for unary minus.
+pub fn parse_postfix(expression: &str) -> Result<Expr, EvalError> {
+    build_tree(&tokenize_postfix(expression)?)
+}
+
+pub fn infix_to_postfix(expression: &str) -> Result<String, EvalError> {
+    parse_infix(expression).map(|expr| expr.to_postfix())
+}
+
+pub fn postfix_to_infix(expression: &str) -> Result<String, EvalError> {
+    parse_postfix(expression).map(|expr| expr.to_string())
+}
+
+pub fn evaluate_postfix(
+    expression: &str,
+    variables: &HashMap<String, f64>,
+) -> Result<f64, EvalError> {
+    evaluate_postfix_items(&tokenize_postfix(expression)?, variables)
 }
 
 #[cfg(test)]
 mod tests {
+    use rand::{thread_rng, Rng};
+
     use super::*;
 
+    fn random_list(len: usize, range: i32) -> Vec<i32> {
+        let mut rng = thread_rng();
+
+        (0..len).map(|_| rng.gen_range(-range..=range)).collect()
+    }
+
+    #[test]
+    fn two_sum_variants() {
+        (0..20).for_each(|_| {
+            let list = random_list(200, 10);
+            let expected = two_sum(&list);
+
+            assert_eq!(two_sum_fast(&mut list.clone()), expected);
+            assert_eq!(two_sum_hash(&list), expected);
+            assert_eq!(two_sum_pairs(&list).count(), expected);
+            assert!(two_sum_pairs(&list).all(|(i, j)| i < j && list[i] == -list[j]));
+        });
+    }
+
+    #[test]
+    fn three_sum_variants() {
+        (0..20).for_each(|_| {
+            let list = random_list(100, 10);
+            let expected = three_sum(&list);
+
+            assert_eq!(three_sum_binary(&mut list.clone()), expected);
+            assert_eq!(three_sum_fast(&mut list.clone()), expected);
+            assert_eq!(three_sum_triples(&list).count(), expected);
+            assert!(three_sum_triples(&list)
+                .all(|(i, j, k)| i < j && j < k && list[i] + list[j] + list[k] == 0));
+        });
+
+        assert_eq!(three_sum_fast(&mut [0, 0, 0, 0]), 4);
+        assert_eq!(
+            three_sum_values(&mut [-1, 0, 1, 2, -1, -4, 0, 0]).collect::<Vec<_>>(),
+            vec![(-1, -1, 2), (-1, 0, 1), (0, 0, 0)]
+        );
+    }
+
+    #[test]
+    fn no_overflow() {
+        let mut list = [i32::MAX, i32::MAX, 2, i32::MIN, i32::MIN];
+
+        assert_eq!(three_sum(&list), 0);
+        assert_eq!(three_sum_binary(&mut list.clone()), 0);
+        assert_eq!(three_sum_fast(&mut list.clone()), 0);
+        assert_eq!(three_sum_triples(&list).count(), 0);
+        assert_eq!(three_sum_values(&mut list.clone()).count(), 0);
+
+        assert_eq!(two_sum(&list), 0);
+        assert_eq!(two_sum_fast(&mut list), 0);
+        assert_eq!(two_sum_hash(&[i32::MIN, i32::MIN]), 0);
+    }
+
     #[test]
     fn evaluate() {
         assert_eq!(
             evaluate_arithmetic_expression("( 1 + ( ( 2 + 3 ) * ( 4 * 5 ) ) )"),
-            101.0
+            Ok(101.0)
         );
         assert_eq!(
             evaluate_arithmetic_expression("( ( 1 + sqrt ( 5.0 ) ) / 2.0 )"),
-            1.618033988749895
+            Ok(1.618033988749895)
+        );
+
+        assert_eq!(
+            evaluate_arithmetic_expression(""),
+            Err(EvalError::UnexpectedEnd)
+        );
+        assert!(evaluate_arithmetic_expression(")").is_err());
+        assert!(evaluate_arithmetic_expression("( 1 + x2 )").is_err());
+        assert!(evaluate_arithmetic_expression("( 1 + 2 ) 3").is_err());
+    }
+
+    fn vars(pairs: &[(&str, f64)]) -> HashMap<String, f64> {
+        pairs
+            .iter()
+            .map(|(name, value)| (name.to_string(), *value))
+            .collect()
+    }
+
+    #[test]
+    fn evaluate_infix() {
+        let none = HashMap::new();
+
+        assert_eq!(evaluate_expression("1+((2+3)*(4*5))", &none), Ok(101.0));
+        assert_eq!(evaluate_expression("1 + 2 * 3", &none), Ok(7.0));
+        assert_eq!(evaluate_expression("8 - 3 - 2", &none), Ok(3.0));
+        assert_eq!(evaluate_expression("2 ^ 3 ^ 2", &none), Ok(512.0));
+        assert_eq!(evaluate_expression("-2^2", &none), Ok(-4.0));
+        assert_eq!(evaluate_expression("2^-1", &none), Ok(0.5));
+        assert_eq!(evaluate_expression("3*-(1+1)", &none), Ok(-6.0));
+        assert_eq!(
+            evaluate_expression("pow(2, 10) + abs(-1.5e1)", &none),
+            Ok(1039.0)
+        );
+        assert_eq!(
+            evaluate_expression("ln(1) + sin(0) + cos(0)", &none),
+            Ok(1.0)
+        );
+        assert_eq!(
+            evaluate_expression("(1 + sqrt(5.0)) / 2.0", &none),
+            Ok(1.618033988749895)
+        );
+        assert_eq!(
+            evaluate_expression(
+                "x^2 + 2*x*y + y_2",
+                &vars(&[("x", 3.0), ("y", 4.0), ("y_2", 1.0)])
+            ),
+            Ok(34.0)
+        );
+    }
+
+    #[test]
+    fn evaluate_errors() {
+        let none = HashMap::new();
+
+        assert_eq!(
+            evaluate_expression("1 + $", &none),
+            Err(EvalError::UnexpectedCharacter {
+                position: 4,
+                found: '$'
+            })
+        );
+        assert_eq!(
+            evaluate_expression("1.2.3", &none),
+            Err(EvalError::InvalidNumber {
+                position: 0,
+                text: "1.2.3".to_string()
+            })
+        );
+        assert_eq!(
+            evaluate_expression("1 +", &none),
+            Err(EvalError::UnexpectedEnd)
+        );
+        assert_eq!(
+            evaluate_expression("", &none),
+            Err(EvalError::UnexpectedEnd)
+        );
+        assert_eq!(
+            evaluate_expression("1 2", &none),
+            Err(EvalError::UnexpectedToken { position: 2 })
+        );
+        assert_eq!(
+            evaluate_expression("* 2", &none),
+            Err(EvalError::UnexpectedToken { position: 0 })
+        );
+        assert_eq!(
+            evaluate_expression("(1 + 2", &none),
+            Err(EvalError::UnmatchedParenthesis { position: 0 })
+        );
+        assert_eq!(
+            evaluate_expression("1 + 2)", &none),
+            Err(EvalError::UnmatchedParenthesis { position: 5 })
+        );
+        assert_eq!(
+            evaluate_expression("foo(1)", &none),
+            Err(EvalError::UnknownFunction {
+                position: 0,
+                name: "foo".to_string()
+            })
+        );
+        assert_eq!(
+            evaluate_expression("1 + x", &none),
+            Err(EvalError::UnknownVariable {
+                position: 4,
+                name: "x".to_string()
+            })
         );
+        assert_eq!(
+            evaluate_expression("pow(2)", &none),
+            Err(EvalError::WrongArgumentCount {
+                position: 0,
+                name: "pow".to_string(),
+                expected: 2,
+                found: 1
+            })
+        );
+        assert_eq!(
+            evaluate_expression("(1, 2)", &none),
+            Err(EvalError::UnexpectedToken { position: 0 })
+        );
+    }
+
+    #[test]
+    fn postfix() {
+        let none = HashMap::new();
+
+        assert_eq!(
+            infix_to_postfix("1 + (2 + 3) * (4 * 5)"),
+            Ok("1 2 3 + 4 5 * * +".to_string())
+        );
+        assert_eq!(
+            infix_to_postfix("-x^2 + sqrt(pow(y, 2))"),
+            Ok("x 2 ^ neg y 2 ^ sqrt +".to_string())
+        );
+        assert_eq!(
+            postfix_to_infix("1 2 3 + 4 5 * * +"),
+            Ok("1 + (2 + 3) * (4 * 5)".to_string())
+        );
+        assert_eq!(evaluate_postfix("1 2 3 + 4 5 * * +", &none), Ok(101.0));
+        assert_eq!(evaluate_postfix("3 -4 + 2 ^ neg", &none), Ok(-1.0));
+        assert_eq!(evaluate_postfix("x 1 +", &vars(&[("x", 2.0)])), Ok(3.0));
+
+        assert_eq!(evaluate_postfix("", &none), Err(EvalError::UnexpectedEnd));
+        assert_eq!(
+            evaluate_postfix("1 +", &none),
+            Err(EvalError::UnexpectedToken { position: 2 })
+        );
+        assert_eq!(
+            evaluate_postfix("1 2 3 +", &none),
+            Err(EvalError::UnexpectedToken { position: 2 })
+        );
+        assert_eq!(
+            parse_postfix("1 2 %"),
+            Err(EvalError::UnexpectedCharacter {
+                position: 4,
+                found: '%'
+            })
+        );
+    }
+
+    #[test]
+    fn minimal_parentheses() {
+        [
+            "1 + 2 + 3",
+            "1 + (2 + 3)",
+            "1 - (2 - 3)",
+            "(1 - 2) * 3",
+            "2 ^ 3 ^ 2",
+            "(2 ^ 3) ^ 2",
+            "-2 ^ 2",
+            "(-2) ^ 2",
+            "2 ^ -3 ^ 2",
+            "-(a + b) * c",
+            "a * -b + c",
+            "a - -(b * c)",
+            "sqrt(x + 1) / abs(y)",
+        ]
+        .iter()
+        .for_each(|&infix| {
+            let expr = parse_infix(infix).unwrap();
+
+            assert_eq!(expr.to_string(), infix);
+            assert_eq!(parse_postfix(&expr.to_postfix()), Ok(expr.clone()));
+            assert_eq!(parse_infix(&expr.to_string()), Ok(expr));
+        });
+
+        assert_eq!(
+            parse_infix("((1)) + ((x) * (y))").unwrap().to_string(),
+            "1 + x * y"
+        );
+        assert_eq!(
+            Expr::Binary(
+                BinaryOperators::Power,
+                Box::new(Expr::Number(-2.0)),
+                Box::new(Expr::Number(-3.0))
+            )
+            .to_string(),
+            "(-2) ^ -3"
+        );
+    }
+
+    #[test]
+    fn negative_literal_round_trip() {
+        let none = HashMap::new();
+        let expr = parse_postfix("-2 2 ^").unwrap();
+        assert_eq!(
+            expr,
+            Expr::Binary(
+                BinaryOperators::Power,
+                Box::new(Expr::Number(-2.0)),
+                Box::new(Expr::Number(2.0))
+            )
+        );
+
+        let infix = expr.to_string();
+        assert_eq!(infix, "(-2) ^ 2");
+        assert_eq!(postfix_to_infix("-2 2 ^"), Ok(infix.clone()));
+
+        // a negated literal rather than the literal, with the same value
+        let parsed = parse_infix(&infix).unwrap();
+        assert_eq!(
+            parsed,
+            Expr::Binary(
+                BinaryOperators::Power,
+                Box::new(Expr::Unary(
+                    UnaryOperators::Negate,
+                    Box::new(Expr::Number(2.0))
+                )),
+                Box::new(Expr::Number(2.0))
+            )
+        );
+        assert_eq!(parsed.evaluate(&none), Some(4.0));
+        assert_eq!(expr.evaluate(&none), Some(4.0));
+    }
+
+    #[test]
+    fn evaluate_tree() {
+        let expr = parse_infix("x * (y - 1)").unwrap();
+
+        assert_eq!(expr.evaluate(&vars(&[("x", 3.0), ("y", 5.0)])), Some(12.0));
+        assert_eq!(expr.evaluate(&vars(&[("x", 3.0)])), None);
     }
 }
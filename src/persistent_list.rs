@@ -0,0 +1,231 @@
+use std::{fmt::Debug, rc::Rc};
+
+/// Immutable singly linked list. `prepend` and `tail` return new lists that
+/// share every existing node, so keeping old versions around is cheap.
+pub struct PersistentList<T> {
+    head: Link<T>,
+}
+
+type Link<T> = Option<Rc<Node<T>>>;
+
+struct Node<T> {
+    elem: T,
+    /// length of the list starting at this node
+    len: usize,
+    next: Link<T>,
+}
+
+impl<T> PersistentList<T> {
+    pub fn new() -> Self {
+        PersistentList { head: None }
+    }
+
+    // [A] -> (X) -> (Y)
+    // prepend Z
+    // [B] -> (Z) -> (X) -> (Y), [A] still points to (X)
+    pub fn prepend(&self, elem: T) -> Self {
+        PersistentList {
+            head: Some(Rc::new(Node {
+                elem,
+                len: self.len() + 1,
+                next: self.head.clone(),
+            })),
+        }
+    }
+
+    /// the list without its first element; empty stays empty
+    pub fn tail(&self) -> Self {
+        PersistentList {
+            head: self.head.as_ref().and_then(|node| node.next.clone()),
+        }
+    }
+
+    pub fn head(&self) -> Option<&T> {
+        self.head.as_ref().map(|node| &node.elem)
+    }
+
+    pub fn len(&self) -> usize {
+        self.head.as_ref().map_or(0, |node| node.len)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.head.is_none()
+    }
+
+    /// whether both lists start at the very same node
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        match (&self.head, &other.head) {
+            (Some(a), Some(b)) => Rc::ptr_eq(a, b),
+            (None, None) => true,
+            _ => false,
+        }
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            next: self.head.as_deref(),
+        }
+    }
+}
+
+impl<T> Default for PersistentList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// O(1): only the head pointer is copied.
+impl<T> Clone for PersistentList<T> {
+    fn clone(&self) -> Self {
+        PersistentList {
+            head: self.head.clone(),
+        }
+    }
+}
+
+/// The default drop would recurse once per node and overflow the stack on
+/// long lists, so unlink nodes one at a time until one is still shared.
+impl<T> Drop for PersistentList<T> {
+    fn drop(&mut self) {
+        let mut head = self.head.take();
+
+        while let Some(node) = head {
+            match Rc::try_unwrap(node) {
+                Ok(mut node) => head = node.next.take(),
+                Err(_) => break,
+            }
+        }
+    }
+}
+
+/// The first item of the iterator ends up at the head.
+impl<T> FromIterator<T> for PersistentList<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let items: Vec<T> = iter.into_iter().collect();
+
+        items
+            .into_iter()
+            .rev()
+            .fold(PersistentList::new(), |list, elem| list.prepend(elem))
+    }
+}
+
+impl<T: Debug> Debug for PersistentList<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T: PartialEq> PartialEq for PersistentList<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Eq> Eq for PersistentList<T> {}
+
+pub struct Iter<'a, T> {
+    next: Option<&'a Node<T>>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next.map(|node| {
+            self.next = node.next.as_deref();
+            &node.elem
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.next.map_or(0, |node| node.len);
+        (len, Some(len))
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+impl<'a, T> IntoIterator for &'a PersistentList<T> {
+    type Item = &'a T;
+
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::PersistentList;
+
+    #[test]
+    fn basics() {
+        let list = PersistentList::new();
+        assert_eq!(list.head(), None);
+        assert_eq!(list.tail().len(), 0);
+
+        let list = list.prepend(1).prepend(2).prepend(3);
+        assert_eq!(list.head(), Some(&3));
+        assert_eq!(list.len(), 3);
+
+        let list = list.tail();
+        assert_eq!(list.head(), Some(&2));
+
+        let list = list.tail().tail();
+        assert_eq!(list.head(), None);
+        assert!(list.is_empty());
+    }
+
+    #[test]
+    fn structural_sharing() {
+        let base: PersistentList<i32> = (1..=3).collect();
+        let a = base.prepend(10);
+        let b = base.prepend(20);
+
+        assert!(a.tail().ptr_eq(&base));
+        assert!(b.tail().ptr_eq(&base));
+        assert!(!a.ptr_eq(&b));
+
+        assert_eq!(a.iter().copied().collect::<Vec<_>>(), [10, 1, 2, 3]);
+        assert_eq!(b.iter().copied().collect::<Vec<_>>(), [20, 1, 2, 3]);
+
+        // dropping one version leaves the shared nodes alone
+        drop(a);
+        assert_eq!(format!("{:?}", base), "[1, 2, 3]");
+        assert_eq!(b.tail(), base);
+    }
+
+    #[test]
+    fn undo_history() {
+        let mut history = PersistentList::new();
+        let mut document = String::new();
+
+        ["a", "b", "c"].iter().for_each(|edit| {
+            history = history.prepend(document.clone());
+            document.push_str(edit);
+        });
+        assert_eq!(document, "abc");
+
+        // undo twice
+        (0..2).for_each(|_| {
+            document = history.head().unwrap().clone();
+            history = history.tail();
+        });
+        assert_eq!(document, "a");
+        assert_eq!(history.len(), 1);
+    }
+
+    #[test]
+    fn long_list_drop() {
+        let list: PersistentList<usize> = (0..1_000_000).collect();
+        let shared = list.tail();
+
+        assert_eq!(list.iter().len(), 1_000_000);
+
+        drop(list);
+        assert_eq!(shared.len(), 999_999);
+        drop(shared);
+    }
+}
@@ -1,28 +1,82 @@
 use std::{
-    alloc::{alloc, dealloc, Layout},
-    ptr::{self, null_mut},
+    alloc::{alloc, dealloc, handle_alloc_error, Layout},
+    marker::PhantomData,
+    mem,
+    ptr::{self, NonNull},
 };
 
 pub struct List<T> {
     head: Link<T>,
     tail: Link<T>,
+    len: usize,
+    /// We own boxed nodes: drop check knows we drop T, and List<T> is
+    /// covariant in T just like Box<T>.
+    _owns: PhantomData<Box<Node<T>>>,
 }
 
-type Link<T> = *mut Node<T>;
+/// `*const` rather than `*mut` so the list stays covariant in T. Every node
+/// is allocated mutable, so casting back with `cast_mut` to write is fine.
+type Link<T> = *const Node<T>;
 
 struct Node<T> {
     elem: T,
     next: Link<T>,
 }
 
+impl<T> Node<T> {
+    fn alloc(elem: T, next: Link<T>) -> Link<T> {
+        let layout = Layout::new::<Node<T>>();
+
+        unsafe {
+            // Allocating zero bytes is UB. A node always holds its `next`
+            // pointer so this never happens today, but don't rely on it.
+            let node = if layout.size() == 0 {
+                NonNull::<Node<T>>::dangling().as_ptr()
+            } else {
+                alloc(layout) as *mut Node<T>
+            };
+
+            if node.is_null() {
+                handle_alloc_error(layout);
+            }
+
+            ptr::write(node, Node { elem, next });
+
+            node
+        }
+    }
+
+    /// Safety: `node` came from `Node::alloc` and is no longer reachable
+    unsafe fn free(node: Link<T>) -> T {
+        let layout = Layout::new::<Node<T>>();
+        let Node { elem, .. } = ptr::read(node);
+
+        if layout.size() != 0 {
+            dealloc(node as *mut u8, layout);
+        }
+
+        elem
+    }
+}
+
 impl<T> List<T> {
     pub fn new() -> Self {
         List {
-            head: ptr::null_mut(),
-            tail: ptr::null_mut(),
+            head: ptr::null(),
+            tail: ptr::null(),
+            len: 0,
+            _owns: PhantomData,
         }
     }
 
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
     // [null, null]
     // push_back X
     // [ptr, ptr] -> (X, null)
@@ -30,23 +84,16 @@ impl<T> List<T> {
     // [ptr, ptr] -> (X, ptr) -> (Y, null)
     pub fn push_back(&mut self, elem: T) {
         unsafe {
-            let layout = Layout::new::<Node<T>>();
-            let new_tail = alloc(layout) as Link<T>;
-            ptr::write(
-                new_tail,
-                Node {
-                    elem,
-                    next: ptr::null_mut(),
-                },
-            );
+            let new_tail = Node::alloc(elem, ptr::null());
 
             if !self.tail.is_null() {
-                (*self.tail).next = new_tail;
+                (*self.tail.cast_mut()).next = new_tail;
             } else {
                 self.head = new_tail;
             }
 
             self.tail = new_tail;
+            self.len += 1;
         }
     }
 
@@ -58,21 +105,14 @@ impl<T> List<T> {
     // push_front Z
     // [ptr, ptr] -> (Z, ptr) -> (Y, ptr) -> (X, null)
     pub fn push_front(&mut self, elem: T) {
-        unsafe {
-            let layout = Layout::new::<Node<T>>();
-            let new_head = alloc(layout) as Link<T>;
+        let new_head = Node::alloc(elem, self.head);
 
-            *new_head = Node {
-                elem,
-                next: self.head,
-            };
-
-            if self.head.is_null() {
-                self.tail = new_head;
-            }
-
-            self.head = new_head
+        if self.head.is_null() {
+            self.tail = new_head;
         }
+
+        self.head = new_head;
+        self.len += 1;
     }
 
     // [ptr, ptr] -> (X, ptr) -> (Y, null)
@@ -84,23 +124,22 @@ impl<T> List<T> {
     // nothing
     pub fn pop_front(&mut self) -> Option<T> {
         unsafe {
-            let layout = Layout::new::<Node<T>>();
             let result;
 
             if self.head.is_null() {
                 result = None
             } else {
-                let head = ptr::read(self.head);
+                let head = self.head;
 
-                result = Some(head.elem);
+                self.head = (*head).next;
 
-                dealloc(self.head as *mut u8, layout);
-
-                self.head = head.next;
+                result = Some(Node::free(head));
 
                 if self.head.is_null() {
-                    self.tail = ptr::null_mut();
+                    self.tail = ptr::null();
                 }
+
+                self.len -= 1;
             }
 
             result
@@ -121,7 +160,6 @@ impl<T> List<T> {
     // To make it O(1) we need the scary Doubly Linked List
     pub fn pop_back(&mut self) -> Option<T> {
         unsafe {
-            let layout = Layout::new::<Node<T>>();
             let result;
 
             if self.tail.is_null() {
@@ -130,23 +168,23 @@ impl<T> List<T> {
                 let mut p = self.head;
 
                 if (*p).next.is_null() {
-                    result = Some(ptr::read(p).elem);
-                    dealloc(p as *mut u8, layout);
+                    result = Some(Node::free(p));
 
-                    self.head = ptr::null_mut();
-                    self.tail = ptr::null_mut();
+                    self.head = ptr::null();
+                    self.tail = ptr::null();
                 } else {
                     while !(*(*p).next).next.is_null() {
                         p = (*p).next;
                     }
 
-                    (*p).next = ptr::null_mut();
+                    (*p.cast_mut()).next = ptr::null();
 
-                    result = Some(ptr::read(self.tail).elem);
-                    dealloc(self.tail as *mut u8, layout);
+                    result = Some(Node::free(self.tail));
 
                     self.tail = p;
                 }
+
+                self.len -= 1;
             }
 
             result
@@ -158,7 +196,7 @@ impl<T> List<T> {
     }
 
     pub fn front_mut(&mut self) -> Option<&mut T> {
-        unsafe { self.head.as_mut().map(|node| &mut node.elem) }
+        unsafe { self.head.cast_mut().as_mut().map(|node| &mut node.elem) }
     }
 
     pub fn back(&self) -> Option<&T> {
@@ -166,7 +204,7 @@ impl<T> List<T> {
     }
 
     pub fn back_mut(&mut self) -> Option<&mut T> {
-        unsafe { self.tail.as_mut().map(|node| &mut node.elem) }
+        unsafe { self.tail.cast_mut().as_mut().map(|node| &mut node.elem) }
     }
 
     pub fn iter(&self) -> Iter<'_, T> {
@@ -180,15 +218,237 @@ impl<T> List<T> {
     pub fn iter_mut(&mut self) -> IterMut<'_, T> {
         unsafe {
             IterMut {
-                next: self.head.as_mut(),
+                next: self.head.cast_mut().as_mut(),
+            }
+        }
+    }
+
+    /// A cursor resting on the "ghost" position before the head, so the
+    /// first node can be removed or inserted before like any other.
+    pub fn cursor_mut(&mut self) -> CursorMut<'_, T> {
+        CursorMut {
+            current: ptr::null(),
+            index: None,
+            list: self,
+        }
+    }
+
+    /// Move all of `other` to the back of this list in O(1), leaving it empty.
+    pub fn append(&mut self, other: &mut List<T>) {
+        if other.head.is_null() {
+            return;
+        }
+
+        if self.tail.is_null() {
+            self.head = other.head;
+        } else {
+            unsafe {
+                (*self.tail.cast_mut()).next = other.head;
+            }
+        }
+
+        self.tail = other.tail;
+        self.len += other.len;
+
+        other.head = ptr::null();
+        other.tail = ptr::null();
+        other.len = 0;
+    }
+
+    // [ptr, ptr] -> (X, ptr) -> (Y, ptr) -> (Z, null)
+    // reverse
+    // [ptr, ptr] -> (Z, ptr) -> (Y, ptr) -> (X, null)
+    pub fn reverse(&mut self) {
+        let mut prev: Link<T> = ptr::null();
+        let mut current = self.head;
+
+        while !current.is_null() {
+            unsafe {
+                let next = (*current).next;
+                (*current.cast_mut()).next = prev;
+                prev = current;
+                current = next;
+            }
+        }
+
+        self.tail = self.head;
+        self.head = prev;
+    }
+
+    /// Keep only the elements for which `f` returns true, in order.
+    pub fn retain<F: FnMut(&T) -> bool>(&mut self, mut f: F) {
+        let mut cursor = self.cursor_mut();
+
+        while let Some(elem) = cursor.peek_next() {
+            if f(elem) {
+                cursor.move_next();
+            } else {
+                cursor.remove_next();
             }
         }
     }
 }
 
+/// Walks a list front to back and edits it around the current position.
+/// After the last node the cursor returns to the ghost position.
+pub struct CursorMut<'a, T> {
+    list: &'a mut List<T>,
+    /// null for the ghost position before the head
+    current: Link<T>,
+    index: Option<usize>,
+}
+
+impl<'a, T> CursorMut<'a, T> {
+    /// None at the ghost position
+    pub fn index(&self) -> Option<usize> {
+        self.index
+    }
+
+    fn next_link(&self) -> Link<T> {
+        if self.current.is_null() {
+            self.list.head
+        } else {
+            unsafe { (*self.current).next }
+        }
+    }
+
+    fn set_next_link(&mut self, link: Link<T>) {
+        if self.current.is_null() {
+            self.list.head = link;
+        } else {
+            unsafe {
+                (*self.current.cast_mut()).next = link;
+            }
+        }
+    }
+
+    pub fn move_next(&mut self) {
+        self.current = self.next_link();
+        self.index = if self.current.is_null() {
+            None
+        } else {
+            Some(self.index.map_or(0, |index| index + 1))
+        };
+    }
+
+    pub fn current(&mut self) -> Option<&mut T> {
+        unsafe { self.current.cast_mut().as_mut().map(|node| &mut node.elem) }
+    }
+
+    pub fn peek_next(&mut self) -> Option<&mut T> {
+        unsafe {
+            self.next_link()
+                .cast_mut()
+                .as_mut()
+                .map(|node| &mut node.elem)
+        }
+    }
+
+    pub fn insert_after(&mut self, elem: T) {
+        let node = Node::alloc(elem, self.next_link());
+
+        if self.current == self.list.tail {
+            self.list.tail = node;
+        }
+
+        self.set_next_link(node);
+        self.list.len += 1;
+    }
+
+    pub fn remove_next(&mut self) -> Option<T> {
+        let next = self.next_link();
+
+        if next.is_null() {
+            return None;
+        }
+
+        unsafe {
+            self.set_next_link((*next).next);
+
+            if next == self.list.tail {
+                self.list.tail = self.current;
+            }
+
+            self.list.len -= 1;
+
+            Some(Node::free(next))
+        }
+    }
+
+    /// Detach everything after the cursor into a new list.
+    pub fn split_after(&mut self) -> List<T> {
+        let next = self.next_link();
+        let kept = self.index.map_or(0, |index| index + 1);
+
+        let rest = List {
+            head: next,
+            tail: if next.is_null() {
+                ptr::null()
+            } else {
+                self.list.tail
+            },
+            len: self.list.len - kept,
+            _owns: PhantomData,
+        };
+
+        self.set_next_link(ptr::null());
+        self.list.tail = self.current;
+        self.list.len = kept;
+
+        rest
+    }
+
+    /// Insert all of `other` after the cursor in O(1).
+    pub fn splice_after(&mut self, mut other: List<T>) {
+        if other.head.is_null() {
+            return;
+        }
+
+        unsafe {
+            (*other.tail.cast_mut()).next = self.next_link();
+        }
+
+        if self.current == self.list.tail {
+            self.list.tail = other.tail;
+        }
+
+        self.set_next_link(other.head);
+        self.list.len += other.len;
+
+        other.head = ptr::null();
+        other.tail = ptr::null();
+        other.len = 0;
+    }
+}
+
+// The list owns its nodes like a Box would, so it is as thread-safe as T.
+unsafe impl<T: Send> Send for List<T> {}
+unsafe impl<T: Sync> Sync for List<T> {}
+
+unsafe impl<T: Sync> Send for Iter<'_, T> {}
+unsafe impl<T: Sync> Sync for Iter<'_, T> {}
+
+unsafe impl<T: Send> Send for IterMut<'_, T> {}
+unsafe impl<T: Sync> Sync for IterMut<'_, T> {}
+
 impl<T> Drop for List<T> {
     fn drop(&mut self) {
-        while let Some(_) = self.pop_front() {}
+        // If dropping an element panics, keep dropping the rest while
+        // unwinding instead of leaking them. `pop_front` has already unlinked
+        // and freed the node by the time the element is dropped.
+        struct DropGuard<'a, T>(&'a mut List<T>);
+
+        impl<T> Drop for DropGuard<'_, T> {
+            fn drop(&mut self) {
+                while self.0.pop_front().is_some() {}
+            }
+        }
+
+        while let Some(elem) = self.pop_front() {
+            let guard = DropGuard(self);
+            drop(elem);
+            mem::forget(guard);
+        }
     }
 }
 
@@ -252,7 +512,7 @@ impl<'a, T> Iterator for IterMut<'a, T> {
     fn next(&mut self) -> Option<Self::Item> {
         unsafe {
             self.next.take().map(|node| {
-                self.next = node.next.as_mut();
+                self.next = node.next.cast_mut().as_mut();
                 &mut node.elem
             })
         }
@@ -267,7 +527,7 @@ impl<'a, T> IntoIterator for &'a mut List<T> {
     fn into_iter(self) -> Self::IntoIter {
         unsafe {
             IterMut {
-                next: self.head.as_mut(),
+                next: self.head.cast_mut().as_mut(),
             }
         }
     }
@@ -275,7 +535,15 @@ impl<'a, T> IntoIterator for &'a mut List<T> {
 
 #[cfg(test)]
 mod test {
-    use super::List;
+    //! Everything here should also pass `cargo +nightly miri test singly_linked_list`.
+
+    use std::{
+        cell::Cell,
+        panic::{catch_unwind, AssertUnwindSafe},
+        rc::Rc,
+    };
+
+    use super::{Iter, IterMut, List};
 
     #[test]
     fn pop_back() {
@@ -373,6 +641,172 @@ mod test {
         assert_eq!(iter.next(), None);
     }
 
+    fn list_of(items: &[i32]) -> List<i32> {
+        let mut list = List::new();
+        items.iter().for_each(|&item| list.push_back(item));
+        list
+    }
+
+    fn to_vec(list: &List<i32>) -> Vec<i32> {
+        list.iter().copied().collect()
+    }
+
+    #[test]
+    fn len() {
+        let mut list = list_of(&[1, 2, 3]);
+        assert_eq!(list.len(), 3);
+
+        list.pop_back();
+        list.pop_front();
+        list.push_front(0);
+        assert_eq!(list.len(), 2);
+
+        list.pop_back();
+        list.pop_back();
+        list.pop_back();
+        assert!(list.is_empty());
+    }
+
+    #[test]
+    fn cursor() {
+        let mut list = list_of(&[1, 2, 3]);
+        let mut cursor = list.cursor_mut();
+
+        assert_eq!(cursor.index(), None);
+        assert_eq!(cursor.current(), None);
+        cursor.insert_after(0);
+        assert_eq!(cursor.peek_next(), Some(&mut 0));
+
+        cursor.move_next();
+        cursor.move_next();
+        assert_eq!(cursor.index(), Some(1));
+        assert_eq!(cursor.current(), Some(&mut 1));
+        assert_eq!(cursor.remove_next(), Some(2));
+
+        cursor.move_next();
+        assert_eq!(cursor.current(), Some(&mut 3));
+        assert_eq!(cursor.remove_next(), None);
+        cursor.insert_after(4);
+
+        cursor.move_next();
+        cursor.move_next();
+        assert_eq!(cursor.index(), None);
+
+        assert_eq!(to_vec(&list), [0, 1, 3, 4]);
+        assert_eq!(list.back(), Some(&4));
+        assert_eq!(list.len(), 4);
+    }
+
+    #[test]
+    fn remove_kth() {
+        let mut list = list_of(&[10, 20, 30, 40]);
+        let mut cursor = list.cursor_mut();
+
+        // stop just before the k-th (0-based) node
+        (0..3).for_each(|_| cursor.move_next());
+        assert_eq!(cursor.remove_next(), Some(40));
+
+        assert_eq!(to_vec(&list), [10, 20, 30]);
+        assert_eq!(list.back(), Some(&30));
+        list.push_back(50);
+        assert_eq!(to_vec(&list), [10, 20, 30, 50]);
+    }
+
+    #[test]
+    fn split_and_splice() {
+        let mut list = list_of(&[1, 2, 3, 4, 5]);
+        let mut cursor = list.cursor_mut();
+        cursor.move_next();
+        cursor.move_next();
+
+        let mut rest = cursor.split_after();
+        assert_eq!(to_vec(&list), [1, 2]);
+        assert_eq!(to_vec(&rest), [3, 4, 5]);
+        assert_eq!((list.len(), rest.len()), (2, 3));
+        assert_eq!(list.back(), Some(&2));
+        rest.push_back(6);
+        list.push_back(7);
+
+        let mut cursor = list.cursor_mut();
+        cursor.move_next();
+        cursor.splice_after(rest);
+        cursor.splice_after(List::new());
+        assert_eq!(to_vec(&list), [1, 3, 4, 5, 6, 2, 7]);
+        assert_eq!(list.len(), 7);
+
+        let all = list.cursor_mut().split_after();
+        assert!(list.is_empty());
+        assert_eq!(list.back(), None);
+        assert_eq!(to_vec(&all), [1, 3, 4, 5, 6, 2, 7]);
+
+        let mut cursor = list.cursor_mut();
+        cursor.splice_after(all);
+        assert_eq!(list.back(), Some(&7));
+    }
+
+    #[test]
+    fn append() {
+        let mut list = list_of(&[1, 2]);
+        let mut other = list_of(&[3, 4]);
+
+        list.append(&mut other);
+        assert!(other.is_empty());
+        assert_eq!(to_vec(&list), [1, 2, 3, 4]);
+
+        other.append(&mut list);
+        other.append(&mut List::new());
+        other.push_back(5);
+        assert_eq!(to_vec(&other), [1, 2, 3, 4, 5]);
+        assert_eq!(other.len(), 5);
+    }
+
+    #[test]
+    fn reverse() {
+        let mut list = list_of(&[1, 2, 3]);
+        list.reverse();
+        assert_eq!(to_vec(&list), [3, 2, 1]);
+
+        list.push_back(0);
+        assert_eq!(to_vec(&list), [3, 2, 1, 0]);
+
+        let mut empty: List<i32> = List::new();
+        empty.reverse();
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn retain() {
+        let mut list = list_of(&[1, 2, 3, 4, 5, 6]);
+        list.retain(|x| x % 2 == 1);
+        assert_eq!(to_vec(&list), [1, 3, 5]);
+        assert_eq!(list.len(), 3);
+
+        list.push_back(7);
+        assert_eq!(list.back(), Some(&7));
+
+        list.retain(|_| false);
+        assert!(list.is_empty());
+        assert_eq!(list.back(), None);
+    }
+
+    #[test]
+    fn merge_sorted() {
+        let mut a = list_of(&[1, 4, 6]);
+        let mut b = list_of(&[2, 3, 7, 8]);
+        let mut cursor = a.cursor_mut();
+
+        while let Some(&mut next) = cursor.peek_next() {
+            while b.front().is_some_and(|&x| x < next) {
+                cursor.insert_after(b.pop_front().unwrap());
+                cursor.move_next();
+            }
+            cursor.move_next();
+        }
+        a.append(&mut b);
+
+        assert_eq!(to_vec(&a), [1, 2, 3, 4, 6, 7, 8]);
+    }
+
     #[test]
     fn miri_food() {
         let mut list = List::new();
@@ -410,4 +844,130 @@ mod test {
 
         // Drop it on the ground and let the dtor exercise itself
     }
+
+    #[test]
+    fn zero_sized_types() {
+        let mut list = List::new();
+
+        list.push_back(());
+        list.push_front(());
+        list.push_back(());
+        assert_eq!(list.len(), 3);
+        assert_eq!(list.iter().count(), 3);
+
+        let mut cursor = list.cursor_mut();
+        cursor.move_next();
+        cursor.insert_after(());
+        assert_eq!(cursor.remove_next(), Some(()));
+        let rest = cursor.split_after();
+        assert_eq!((list.len(), rest.len()), (1, 2));
+
+        list.append(&mut List::new());
+        list.cursor_mut().splice_after(rest);
+        list.reverse();
+        list.retain(|_| true);
+        assert_eq!(list.pop_back(), Some(()));
+        assert_eq!(list.pop_front(), Some(()));
+        assert_eq!(list.into_iter().count(), 1);
+    }
+
+    struct DropCounter<'a> {
+        drops: &'a Cell<usize>,
+        panics: bool,
+    }
+
+    impl Drop for DropCounter<'_> {
+        fn drop(&mut self) {
+            self.drops.set(self.drops.get() + 1);
+
+            if self.panics {
+                panic!("panic in drop");
+            }
+        }
+    }
+
+    #[test]
+    fn panicking_drop() {
+        let drops = Cell::new(0);
+        let mut list = List::new();
+
+        (0..5).for_each(|i| {
+            list.push_back(DropCounter {
+                drops: &drops,
+                panics: i == 1,
+            })
+        });
+
+        let result = catch_unwind(AssertUnwindSafe(|| drop(list)));
+
+        assert!(result.is_err());
+        // the elements after the panicking one were still dropped
+        assert_eq!(drops.get(), 5);
+    }
+
+    #[test]
+    fn drops_every_element() {
+        let counter = Rc::new(());
+        let mut list = List::new();
+
+        (0..10).for_each(|_| list.push_back(Rc::clone(&counter)));
+        list.pop_back();
+        list.cursor_mut().remove_next();
+        let rest = {
+            let mut cursor = list.cursor_mut();
+            cursor.move_next();
+            cursor.split_after()
+        };
+        assert_eq!(Rc::strong_count(&counter), 9);
+
+        drop(rest);
+        assert_eq!(Rc::strong_count(&counter), 2);
+
+        drop(list);
+        assert_eq!(Rc::strong_count(&counter), 1);
+    }
+
+    #[test]
+    fn send_sync() {
+        fn is_send<T: Send>() {}
+        fn is_sync<T: Sync>() {}
+
+        is_send::<List<i32>>();
+        is_sync::<List<i32>>();
+        is_send::<Iter<'_, i32>>();
+        is_sync::<Iter<'_, i32>>();
+        is_send::<IterMut<'_, i32>>();
+        is_sync::<IterMut<'_, i32>>();
+
+        let mut list = List::new();
+        list.push_back(1);
+        let list = std::thread::spawn(move || {
+            list.push_back(2);
+            list
+        })
+        .join()
+        .unwrap();
+        assert_eq!(list.iter().sum::<i32>(), 3);
+    }
+
+    #[test]
+    fn covariance() {
+        // these only compile if List<T> and Iter<T> are covariant in T
+        fn shorten_list<'a>(list: List<&'static str>) -> List<&'a str> {
+            list
+        }
+
+        fn shorten_iter<'i, 'a>(iter: Iter<'i, &'static str>) -> Iter<'i, &'a str> {
+            iter
+        }
+
+        let mut list = List::new();
+        list.push_back("static");
+        assert_eq!(shorten_iter(list.iter()).count(), 1);
+
+        let local = String::from("local");
+        let mut list = shorten_list(list);
+        list.push_back(&local);
+        assert_eq!(list.len(), 2);
+    }
 }
@@ -0,0 +1,179 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use rand::{seq::SliceRandom, thread_rng};
+use rayon::prelude::*;
+
+/// Lock-free union-find in the style of Jayanti and Tarjan: parent pointers
+/// are atomics, roots are linked with a single compare-and-swap following a
+/// random total order of the sites, and `find` does path splitting with CAS.
+///
+/// All operations take `&self`, so one instance can be shared across threads.
+pub struct ConcurrentUnionFind {
+    parent: Vec<AtomicUsize>,
+    /// random permutation, a root is always linked under a root of higher
+    /// priority so no cycle can form
+    priority: Vec<usize>,
+    count: AtomicUsize,
+}
+
+impl ConcurrentUnionFind {
+    pub fn new(count: usize) -> Self {
+        let mut priority: Vec<usize> = (0..count).collect();
+        priority.shuffle(&mut thread_rng());
+
+        ConcurrentUnionFind {
+            parent: (0..count).map(AtomicUsize::new).collect(),
+            priority,
+            count: AtomicUsize::new(count),
+        }
+    }
+
+    pub fn find(&self, mut p: usize) -> usize {
+        loop {
+            let parent = self.parent[p].load(Ordering::Acquire);
+            if parent == p {
+                return p;
+            }
+
+            let grandparent = self.parent[parent].load(Ordering::Acquire);
+            if parent != grandparent {
+                // losing this race only means someone else shortened the path
+                let _ = self.parent[p].compare_exchange(
+                    parent,
+                    grandparent,
+                    Ordering::AcqRel,
+                    Ordering::Acquire,
+                );
+            }
+
+            p = parent;
+        }
+    }
+
+    /// merge the components of p and q, false if they were already connected
+    pub fn union(&self, p: usize, q: usize) -> bool {
+        let (mut p, mut q) = (p, q);
+
+        loop {
+            let mut p_root = self.find(p);
+            let mut q_root = self.find(q);
+
+            if p_root == q_root {
+                return false;
+            }
+
+            if self.priority[p_root] > self.priority[q_root] {
+                std::mem::swap(&mut p_root, &mut q_root);
+            }
+
+            if self.parent[p_root]
+                .compare_exchange(p_root, q_root, Ordering::AcqRel, Ordering::Acquire)
+                .is_ok()
+            {
+                self.count.fetch_sub(1, Ordering::AcqRel);
+                return true;
+            }
+
+            // p_root got linked by another thread, retry from the roots we saw
+            p = p_root;
+            q = q_root;
+        }
+    }
+
+    pub fn connected(&self, p: usize, q: usize) -> bool {
+        loop {
+            let p_root = self.find(p);
+            let q_root = self.find(q);
+
+            if p_root == q_root {
+                return true;
+            }
+
+            // still a root after q_root was found, so they were apart right then
+            if self.parent[p_root].load(Ordering::Acquire) == p_root {
+                return false;
+            }
+        }
+    }
+
+    /// number of components
+    pub fn count(&self) -> usize {
+        self.count.load(Ordering::Acquire)
+    }
+
+    pub fn len(&self) -> usize {
+        self.parent.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parent.is_empty()
+    }
+
+    /// Union every pair in parallel, returns the number of merges.
+    pub fn union_all(&self, pairs: &[(usize, usize)]) -> usize {
+        pairs.par_iter().filter(|&&(p, q)| self.union(p, q)).count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use crate::union_find::{
+        erdos_renyi_pairs, parse_params_from_file, union_find, UnionFind, WeightedQuickUnion,
+    };
+
+    use super::*;
+
+    #[test]
+    fn matches_sequential() {
+        let count = 50_000;
+        let pairs = erdos_renyi_pairs(count);
+
+        [pairs.len() / 4, pairs.len() / 2, pairs.len()]
+            .iter()
+            .for_each(|&len| {
+                let mut expected = WeightedQuickUnion::new(count);
+                let expected_count = union_find(&mut expected, &pairs[..len]);
+
+                let uf = ConcurrentUnionFind::new(count);
+                let merges = uf.union_all(&pairs[..len]);
+
+                assert_eq!(uf.count(), expected_count);
+                assert_eq!(merges, count - expected_count);
+
+                (0..1_000).for_each(|p| {
+                    let q = (p * 7919) % count;
+                    assert_eq!(uf.connected(p, q), expected.connected(p, q));
+                });
+            });
+    }
+
+    #[test]
+    fn shared_between_threads() {
+        let count = 10_000;
+        let uf = ConcurrentUnionFind::new(count);
+
+        // every thread links the same chain, only one merge per edge can win
+        let merges: usize = thread::scope(|scope| {
+            let handles: Vec<_> = (0..4)
+                .map(|_| scope.spawn(|| (1..count).filter(|&p| uf.union(p - 1, p)).count()))
+                .collect();
+
+            handles.into_iter().map(|h| h.join().unwrap()).sum()
+        });
+
+        assert_eq!(merges, count - 1);
+        assert_eq!(uf.count(), 1);
+        assert!(uf.connected(0, count - 1));
+        assert!(!uf.union(0, count - 1));
+    }
+
+    #[test]
+    fn large_uf() {
+        let params = parse_params_from_file("algs4-data/largeUF.txt").unwrap();
+        let uf = ConcurrentUnionFind::new(params.count);
+        uf.union_all(&params.pairs);
+        assert_eq!(uf.count(), 6);
+    }
+}
@@ -0,0 +1,727 @@
+mod concurrent;
+mod percolation;
+mod rollback;
+
+pub use concurrent::*;
+pub use percolation::*;
+pub use rollback::*;
+
+use core::num;
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{BufReader, Read},
+    path::Path,
+    time::{Duration, Instant},
+};
+
+use rand::{thread_rng, Rng};
+
+use crate::input::{read_pairs, ParseError};
+
+pub trait UnionFind {
+    fn new(count: usize) -> Self;
+
+    /// merge the components of p and q, false if they were already connected
+    fn union(&mut self, p: usize, q: usize) -> bool;
+
+    fn find(&mut self, p: usize) -> usize;
+
+    /// same root as `find`, but without compressing the path
+    fn find_immutable(&self, p: usize) -> usize;
+
+    /// number of components
+    fn count(&self) -> usize;
+
+    /// number of elements
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// number of elements in the component of p
+    fn component_size(&self, p: usize) -> usize;
+
+    /// add a new singleton component and return its element
+    fn add_element(&mut self) -> usize;
+
+    fn connected(&mut self, p: usize, q: usize) -> bool {
+        self.find(p) == self.find(q)
+    }
+
+    /// members of every component, each group sorted and groups ordered by
+    /// their smallest member
+    fn components(&self) -> Vec<Vec<usize>> {
+        let mut group_of_root: HashMap<usize, usize> = HashMap::new();
+        let mut groups: Vec<Vec<usize>> = Vec::with_capacity(self.count());
+
+        (0..self.len()).for_each(|p| {
+            let root = self.find_immutable(p);
+            let group = *group_of_root.entry(root).or_insert_with(|| {
+                groups.push(Vec::new());
+                groups.len() - 1
+            });
+            groups[group].push(p);
+        });
+
+        groups
+    }
+
+    /// back to all singletons, keeping the number of elements
+    fn reset(&mut self)
+    where
+        Self: Sized,
+    {
+        *self = Self::new(self.len());
+    }
+}
+
+#[derive(Debug)]
+pub struct QuickFind {
+    ids: Vec<usize>,
+    /// size of the component with that id
+    size: Vec<usize>,
+    count: usize,
+}
+
+impl UnionFind for QuickFind {
+    fn new(count: usize) -> Self {
+        QuickFind {
+            ids: (0..count).collect(),
+            size: vec![1; count],
+            count,
+        }
+    }
+
+    fn union(&mut self, p: usize, q: usize) -> bool {
+        let p_id = self.find(p);
+        let q_id = self.find(q);
+
+        if p_id == q_id {
+            return false;
+        }
+
+        self.ids.iter_mut().for_each(|id| {
+            if *id == p_id {
+                *id = q_id;
+            }
+        });
+
+        self.size[q_id] += self.size[p_id];
+        self.count -= 1;
+
+        true
+    }
+
+    fn find(&mut self, p: usize) -> usize {
+        self.ids[p]
+    }
+
+    fn find_immutable(&self, p: usize) -> usize {
+        self.ids[p]
+    }
+
+    fn count(&self) -> usize {
+        self.count
+    }
+
+    fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    fn component_size(&self, p: usize) -> usize {
+        self.size[self.ids[p]]
+    }
+
+    fn add_element(&mut self) -> usize {
+        let p = self.ids.len();
+
+        self.ids.push(p);
+        self.size.push(1);
+        self.count += 1;
+
+        p
+    }
+}
+
+/// How `union` decides which root goes under the other.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Linking {
+    /// always the root of p under the root of q
+    None,
+    /// smaller tree under the larger one
+    Size,
+    /// shallower tree under the deeper one, by an upper bound on height
+    Rank,
+}
+
+/// How `find` flattens the path it walks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    None,
+    /// point every node on the path at the root, in a second pass
+    Full,
+    /// point every other node at its grandparent
+    Halving,
+    /// point every node at its grandparent
+    Splitting,
+}
+
+/// Walk from p up to its root.
+fn root_of(ids: &[usize], p: usize) -> usize {
+    let mut root = p;
+    while root != ids[root] {
+        root = ids[root]
+    }
+
+    root
+}
+
+fn find_with(ids: &mut [usize], mut p: usize, compression: Compression) -> usize {
+    match compression {
+        Compression::None => root_of(ids, p),
+        Compression::Full => {
+            let root = root_of(ids, p);
+
+            while p != root {
+                let p_id = ids[p];
+                ids[p] = root;
+                p = p_id;
+            }
+
+            root
+        }
+        Compression::Halving => {
+            while p != ids[p] {
+                ids[p] = ids[ids[p]];
+                p = ids[p];
+            }
+
+            p
+        }
+        Compression::Splitting => {
+            while p != ids[p] {
+                let next = ids[p];
+                ids[p] = ids[next];
+                p = next;
+            }
+
+            p
+        }
+    }
+}
+
+/// Height of the tallest tree in the forest, a single node having height 0.
+pub fn tree_height(ids: &[usize]) -> usize {
+    let mut depth: Vec<Option<usize>> = vec![None; ids.len()];
+    let mut path: Vec<usize> = Vec::new();
+
+    (0..ids.len()).for_each(|p| {
+        let mut v = p;
+        while depth[v].is_none() && v != ids[v] {
+            path.push(v);
+            v = ids[v];
+        }
+
+        let mut d = depth[v].unwrap_or(0);
+        depth[v] = Some(d);
+
+        while let Some(w) = path.pop() {
+            d += 1;
+            depth[w] = Some(d);
+        }
+    });
+
+    depth.into_iter().flatten().max().unwrap_or(0)
+}
+
+#[derive(Debug)]
+pub struct QuickUnion {
+    pub ids: Vec<usize>,
+    pub count: usize,
+    /// only meaningful at roots
+    size: Vec<usize>,
+    compression: Compression,
+}
+
+impl QuickUnion {
+    pub fn with_compression(count: usize, compression: Compression) -> Self {
+        QuickUnion {
+            ids: (0..count).collect(),
+            count,
+            size: vec![1; count],
+            compression,
+        }
+    }
+
+    pub fn height(&self) -> usize {
+        tree_height(&self.ids)
+    }
+}
+
+impl UnionFind for QuickUnion {
+    fn new(count: usize) -> Self {
+        QuickUnion::with_compression(count, Compression::Full)
+    }
+
+    fn union(&mut self, p: usize, q: usize) -> bool {
+        let p_id = self.find(p);
+        let q_id = self.find(q);
+
+        if p_id == q_id {
+            return false;
+        }
+
+        self.ids[p_id] = q_id;
+        self.size[q_id] += self.size[p_id];
+        self.count -= 1;
+
+        true
+    }
+
+    fn find(&mut self, p: usize) -> usize {
+        find_with(&mut self.ids, p, self.compression)
+    }
+
+    fn find_immutable(&self, p: usize) -> usize {
+        root_of(&self.ids, p)
+    }
+
+    fn count(&self) -> usize {
+        self.count
+    }
+
+    fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    fn component_size(&self, p: usize) -> usize {
+        self.size[self.find_immutable(p)]
+    }
+
+    fn add_element(&mut self) -> usize {
+        let p = self.ids.len();
+
+        self.ids.push(p);
+        self.size.push(1);
+        self.count += 1;
+
+        p
+    }
+
+    fn reset(&mut self) {
+        *self = QuickUnion::with_compression(self.len(), self.compression);
+    }
+}
+
+pub struct WeightedQuickUnion {
+    pub ids: Vec<usize>,
+    pub size: Vec<usize>,
+    /// upper bound on the height of each root's tree, kept for `Linking::Rank`
+    rank: Vec<u8>,
+    count: usize,
+    linking: Linking,
+    compression: Compression,
+}
+
+impl WeightedQuickUnion {
+    pub fn with_strategy(count: usize, linking: Linking, compression: Compression) -> Self {
+        WeightedQuickUnion {
+            ids: (0..count).collect(),
+            size: (0..count).map(|_| 1).collect(),
+            rank: vec![0; count],
+            count,
+            linking,
+            compression,
+        }
+    }
+
+    pub fn linking(&self) -> Linking {
+        self.linking
+    }
+
+    pub fn compression(&self) -> Compression {
+        self.compression
+    }
+
+    pub fn height(&self) -> usize {
+        tree_height(&self.ids)
+    }
+}
+
+impl UnionFind for WeightedQuickUnion {
+    fn new(count: usize) -> Self {
+        WeightedQuickUnion::with_strategy(count, Linking::Size, Compression::Full)
+    }
+
+    fn union(&mut self, p: usize, q: usize) -> bool {
+        let p_id = self.find(p);
+        let q_id = self.find(q);
+
+        if p_id == q_id {
+            return false;
+        }
+
+        let (child, parent) = match self.linking {
+            Linking::None => (p_id, q_id),
+            Linking::Size if self.size[p_id] < self.size[q_id] => (p_id, q_id),
+            Linking::Size => (q_id, p_id),
+            Linking::Rank if self.rank[p_id] < self.rank[q_id] => (p_id, q_id),
+            Linking::Rank => (q_id, p_id),
+        };
+
+        if self.linking == Linking::Rank && self.rank[child] == self.rank[parent] {
+            self.rank[parent] += 1;
+        }
+
+        self.ids[child] = parent;
+        self.size[parent] += self.size[child];
+        self.count -= 1;
+
+        true
+    }
+
+    fn find(&mut self, p: usize) -> usize {
+        find_with(&mut self.ids, p, self.compression)
+    }
+
+    fn find_immutable(&self, p: usize) -> usize {
+        root_of(&self.ids, p)
+    }
+
+    fn count(&self) -> usize {
+        self.count
+    }
+
+    fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    fn component_size(&self, p: usize) -> usize {
+        self.size[self.find_immutable(p)]
+    }
+
+    fn add_element(&mut self) -> usize {
+        let p = self.ids.len();
+
+        self.ids.push(p);
+        self.size.push(1);
+        self.rank.push(0);
+        self.count += 1;
+
+        p
+    }
+
+    fn reset(&mut self) {
+        *self = WeightedQuickUnion::with_strategy(self.len(), self.linking, self.compression);
+    }
+}
+
+pub struct UnionFindParams {
+    pub count: usize,
+    pub pairs: Vec<(usize, usize)>,
+}
+
+pub fn read_params<R: Read>(reader: R) -> Result<UnionFindParams, ParseError> {
+    let (count, pairs) = read_pairs(reader)?;
+
+    Ok(UnionFindParams { count, pairs })
+}
+
+pub fn parse_params_from_file<P: AsRef<Path>>(path: P) -> Result<UnionFindParams, ParseError> {
+    read_params(BufReader::new(File::open(path)?))
+}
+
+pub fn union_find<U: UnionFind>(uf: &mut U, pairs: &[(usize, usize)]) -> usize {
+    pairs.iter().for_each(|(p, q)| {
+        uf.union(*p, *q);
+    });
+
+    return uf.count();
+}
+
+pub fn erdos_renyi<U: UnionFind>(uf: &mut U, count: usize) -> usize {
+    let mut rng = thread_rng();
+
+    let mut num_connections = 0;
+
+    while uf.count() != 1 {
+        uf.union(rng.gen_range(0..count), rng.gen_range(0..count));
+        num_connections += 1;
+    }
+
+    num_connections
+}
+
+/// Random pairs over `count` sites, generated until they connect everything.
+pub fn erdos_renyi_pairs(count: usize) -> Vec<(usize, usize)> {
+    let mut rng = thread_rng();
+    let mut uf = WeightedQuickUnion::new(count);
+    let mut pairs = Vec::new();
+
+    while uf.count() > 1 {
+        let pair = (rng.gen_range(0..count), rng.gen_range(0..count));
+        uf.union(pair.0, pair.1);
+        pairs.push(pair);
+    }
+
+    pairs
+}
+
+/// Every linking rule combined with every compression strategy.
+pub fn all_strategies() -> Vec<(Linking, Compression)> {
+    let compressions = [
+        Compression::None,
+        Compression::Full,
+        Compression::Halving,
+        Compression::Splitting,
+    ];
+
+    [Linking::None, Linking::Size, Linking::Rank]
+        .iter()
+        .flat_map(|&linking| compressions.iter().map(move |&c| (linking, c)))
+        .collect()
+}
+
+#[derive(Debug)]
+pub struct StrategyReport {
+    pub linking: Linking,
+    pub compression: Compression,
+    pub count: usize,
+    /// height of the tallest tree once all pairs are processed
+    pub height: usize,
+    pub duration: Duration,
+}
+
+/// Run the same pairs through a `WeightedQuickUnion` for each strategy.
+pub fn compare_strategies(
+    count: usize,
+    pairs: &[(usize, usize)],
+    strategies: &[(Linking, Compression)],
+) -> Vec<StrategyReport> {
+    strategies
+        .iter()
+        .map(|&(linking, compression)| {
+            let mut uf = WeightedQuickUnion::with_strategy(count, linking, compression);
+
+            let instant = Instant::now();
+            union_find(&mut uf, pairs);
+            let duration = instant.elapsed();
+
+            StrategyReport {
+                linking,
+                compression,
+                count: uf.count(),
+                height: uf.height(),
+                duration,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// pairs of tinyUF.txt
+    const TINY_PAIRS: [(usize, usize); 11] = [
+        (4, 3),
+        (3, 8),
+        (6, 5),
+        (9, 4),
+        (2, 1),
+        (8, 9),
+        (5, 0),
+        (7, 2),
+        (6, 1),
+        (1, 0),
+        (6, 7),
+    ];
+
+    fn check_api<U: UnionFind>() {
+        let mut uf = U::new(10);
+
+        let merged: Vec<bool> = TINY_PAIRS.iter().map(|&(p, q)| uf.union(p, q)).collect();
+        assert_eq!(
+            merged,
+            [true, true, true, true, true, false, true, true, true, false, false]
+        );
+        assert_eq!(uf.count(), 2);
+        assert_eq!(uf.len(), 10);
+
+        assert!(uf.connected(3, 9));
+        assert!(uf.connected(0, 7));
+        assert!(!uf.connected(0, 9));
+        assert_eq!(uf.find_immutable(8), uf.find(8));
+
+        assert_eq!(uf.component_size(4), 4);
+        assert_eq!(uf.component_size(1), 6);
+        assert_eq!(uf.components(), [vec![0, 1, 2, 5, 6, 7], vec![3, 4, 8, 9]]);
+
+        assert_eq!(uf.add_element(), 10);
+        assert_eq!(uf.count(), 3);
+        assert_eq!(uf.component_size(10), 1);
+        assert!(uf.union(10, 3));
+        assert_eq!(uf.component_size(9), 5);
+        assert_eq!(uf.count(), 2);
+
+        uf.reset();
+        assert_eq!(uf.len(), 11);
+        assert_eq!(uf.count(), 11);
+        assert!(!uf.connected(3, 9));
+    }
+
+    #[test]
+    fn api() {
+        check_api::<QuickFind>();
+        check_api::<QuickUnion>();
+        check_api::<WeightedQuickUnion>();
+    }
+
+    #[test]
+    fn quick_find() {
+        let params = parse_params_from_file("algs4-data/mediumUF.txt").unwrap();
+        let mut qf = QuickFind::new(params.count);
+        union_find(&mut qf, &params.pairs);
+        assert_eq!(qf.count(), 3);
+    }
+
+    #[test]
+    fn quick_union() {
+        let params = parse_params_from_file("algs4-data/mediumUF.txt").unwrap();
+        let mut qu = QuickUnion::new(params.count);
+        union_find(&mut qu, &params.pairs);
+        assert_eq!(qu.count(), 3);
+    }
+
+    #[test]
+    fn weighted_quick_union() {
+        let params = parse_params_from_file("algs4-data/largeUF.txt").unwrap();
+        let mut wqu = WeightedQuickUnion::new(params.count);
+        union_find(&mut wqu, &params.pairs);
+        assert_eq!(wqu.count(), 6);
+    }
+
+    #[test]
+    fn test_erdos_renyi() {
+        let inputs = [1_000, 10_000, 100_000, 1_000_000];
+        let results = inputs.map(|count| {
+            let mut uf = WeightedQuickUnion::new(count);
+            erdos_renyi(&mut uf, count);
+        });
+
+        println!("inputs: {:?}", &inputs);
+        println!("results: {:?}", &results);
+
+        let count = 100_000;
+        let pairs = erdos_renyi_pairs(count);
+        let strategies: Vec<(Linking, Compression)> = all_strategies()
+            .into_iter()
+            .filter(|&(linking, _)| linking != Linking::None)
+            .collect();
+
+        compare_strategies(count, &pairs, &strategies)
+            .iter()
+            .for_each(|report| {
+                assert_eq!(report.count, 1);
+                println!("{:?}", report);
+            });
+    }
+
+    #[test]
+    fn strategies() {
+        let count = 2_000;
+        let pairs = erdos_renyi_pairs(count);
+        let half = &pairs[..pairs.len() / 2];
+        let max_height = (count as f64).log2() as usize;
+
+        let mut expected = WeightedQuickUnion::new(count);
+        union_find(&mut expected, half);
+
+        all_strategies()
+            .into_iter()
+            .for_each(|(linking, compression)| {
+                let mut uf = WeightedQuickUnion::with_strategy(count, linking, compression);
+                assert_eq!(uf.linking(), linking);
+                assert_eq!(uf.compression(), compression);
+
+                union_find(&mut uf, half);
+                assert_eq!(uf.count(), expected.count());
+                assert_eq!(uf.components(), expected.components());
+
+                if linking != Linking::None {
+                    assert!(uf.height() <= max_height);
+                }
+
+                union_find(&mut uf, &pairs[half.len()..]);
+                assert_eq!(uf.count(), 1);
+                assert_eq!(uf.component_size(0), count);
+
+                uf.reset();
+                assert_eq!(uf.count(), count);
+                assert_eq!(uf.linking(), linking);
+            });
+
+        [
+            Compression::None,
+            Compression::Halving,
+            Compression::Splitting,
+        ]
+        .iter()
+        .for_each(|&compression| {
+            let mut uf = QuickUnion::with_compression(count, compression);
+            union_find(&mut uf, half);
+            assert_eq!(uf.components(), expected.components());
+        });
+    }
+
+    #[test]
+    fn tree_heights() {
+        assert_eq!(tree_height(&[0, 1, 2]), 0);
+        assert_eq!(tree_height(&[0, 0, 1, 2, 0]), 3);
+
+        // 0 <- 1 <- 2 <- 3, a single find flattens the path differently
+        let chain = || {
+            let mut uf = WeightedQuickUnion::with_strategy(5, Linking::None, Compression::None);
+            (0..4).for_each(|p| {
+                uf.union(p, p + 1);
+            });
+            uf
+        };
+
+        let mut uf = chain();
+        assert_eq!(uf.ids, [1, 2, 3, 4, 4]);
+        assert_eq!(uf.height(), 4);
+
+        [
+            (Compression::None, vec![1, 2, 3, 4, 4]),
+            (Compression::Full, vec![4, 4, 4, 4, 4]),
+            (Compression::Halving, vec![2, 2, 4, 4, 4]),
+            (Compression::Splitting, vec![2, 3, 4, 4, 4]),
+        ]
+        .into_iter()
+        .for_each(|(compression, ids)| {
+            let mut uf = chain();
+            uf.compression = compression;
+            assert_eq!(uf.find(0), 4);
+            assert_eq!(uf.ids, ids);
+        });
+    }
+
+    #[test]
+    fn compare_large_uf() {
+        let params = parse_params_from_file("algs4-data/largeUF.txt").unwrap();
+        let strategies: Vec<(Linking, Compression)> = all_strategies()
+            .into_iter()
+            .filter(|&strategy| strategy != (Linking::None, Compression::None))
+            .collect();
+
+        compare_strategies(params.count, &params.pairs, &strategies)
+            .iter()
+            .for_each(|report| {
+                assert_eq!(report.count, 6);
+                println!("{:?}", report);
+            });
+    }
+}
@@ -0,0 +1,250 @@
+use rand::{seq::SliceRandom, thread_rng};
+
+use super::{UnionFind, WeightedQuickUnion};
+
+/// An n-by-n grid of sites, each blocked or open. A site is full when an open
+/// path connects it to the top row, and the system percolates when some site
+/// in the bottom row is full. Rows and columns count from 0.
+pub struct Percolation {
+    n: usize,
+    open: Vec<bool>,
+    open_count: usize,
+    /// sites plus a virtual top and a virtual bottom, for `percolates`
+    uf: WeightedQuickUnion,
+    /// sites plus only the virtual top, for `is_full`. Sharing the bottom
+    /// would make every open site connected to the bottom look full once
+    /// the system percolates (backwash).
+    full: WeightedQuickUnion,
+}
+
+impl Percolation {
+    pub fn new(n: usize) -> Self {
+        assert!(n > 0, "grid must have at least one site");
+
+        Percolation {
+            n,
+            open: vec![false; n * n],
+            open_count: 0,
+            uf: WeightedQuickUnion::new(n * n + 2),
+            full: WeightedQuickUnion::new(n * n + 1),
+        }
+    }
+
+    fn top(&self) -> usize {
+        self.n * self.n
+    }
+
+    fn bottom(&self) -> usize {
+        self.n * self.n + 1
+    }
+
+    fn site(&self, row: usize, col: usize) -> usize {
+        assert!(
+            row < self.n && col < self.n,
+            "site ({}, {}) outside a {}x{} grid",
+            row,
+            col,
+            self.n,
+            self.n
+        );
+
+        row * self.n + col
+    }
+
+    pub fn open(&mut self, row: usize, col: usize) {
+        let site = self.site(row, col);
+        if self.open[site] {
+            return;
+        }
+
+        self.open[site] = true;
+        self.open_count += 1;
+
+        if row == 0 {
+            let top = self.top();
+            self.uf.union(site, top);
+            self.full.union(site, top);
+        }
+
+        if row == self.n - 1 {
+            let bottom = self.bottom();
+            self.uf.union(site, bottom);
+        }
+
+        let neighbors = [
+            (row > 0).then(|| site - self.n),
+            (row + 1 < self.n).then(|| site + self.n),
+            (col > 0).then(|| site - 1),
+            (col + 1 < self.n).then(|| site + 1),
+        ];
+
+        neighbors.into_iter().flatten().for_each(|neighbor| {
+            if self.open[neighbor] {
+                self.uf.union(site, neighbor);
+                self.full.union(site, neighbor);
+            }
+        });
+    }
+
+    pub fn is_open(&self, row: usize, col: usize) -> bool {
+        self.open[self.site(row, col)]
+    }
+
+    pub fn is_full(&self, row: usize, col: usize) -> bool {
+        let site = self.site(row, col);
+
+        self.open[site] && self.full.find_immutable(site) == self.full.find_immutable(self.top())
+    }
+
+    pub fn number_of_open_sites(&self) -> usize {
+        self.open_count
+    }
+
+    pub fn percolates(&self) -> bool {
+        self.uf.find_immutable(self.top()) == self.uf.find_immutable(self.bottom())
+    }
+}
+
+/// Monte Carlo estimate of the percolation threshold: in each trial open
+/// random sites of an n-by-n grid until it percolates, and record the
+/// fraction of open sites.
+#[derive(Debug)]
+pub struct PercolationStats {
+    thresholds: Vec<f64>,
+}
+
+impl PercolationStats {
+    pub fn new(n: usize, trials: usize) -> Self {
+        assert!(trials > 0, "need at least one trial");
+
+        let mut rng = thread_rng();
+        let mut sites: Vec<(usize, usize)> = (0..n * n).map(|site| (site / n, site % n)).collect();
+
+        let thresholds = (0..trials)
+            .map(|_| {
+                let mut percolation = Percolation::new(n);
+                sites.shuffle(&mut rng);
+
+                for &(row, col) in &sites {
+                    percolation.open(row, col);
+                    if percolation.percolates() {
+                        break;
+                    }
+                }
+
+                percolation.number_of_open_sites() as f64 / (n * n) as f64
+            })
+            .collect();
+
+        PercolationStats { thresholds }
+    }
+
+    pub fn thresholds(&self) -> &[f64] {
+        &self.thresholds
+    }
+
+    pub fn mean(&self) -> f64 {
+        self.thresholds.iter().sum::<f64>() / self.thresholds.len() as f64
+    }
+
+    /// sample standard deviation, NaN for a single trial
+    pub fn stddev(&self) -> f64 {
+        let mean = self.mean();
+        let variance = self
+            .thresholds
+            .iter()
+            .map(|x| (x - mean).powi(2))
+            .sum::<f64>()
+            / (self.thresholds.len() as f64 - 1.0);
+
+        variance.sqrt()
+    }
+
+    /// (low, high) endpoints of the 95% confidence interval for the mean
+    pub fn confidence_interval(&self) -> (f64, f64) {
+        let mean = self.mean();
+        let margin = 1.96 * self.stddev() / (self.thresholds.len() as f64).sqrt();
+
+        (mean - margin, mean + margin)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn percolation() {
+        let mut p = Percolation::new(3);
+        assert!(!p.percolates());
+        assert!(!p.is_full(0, 0));
+
+        p.open(0, 1);
+        p.open(0, 1);
+        assert_eq!(p.number_of_open_sites(), 1);
+        assert!(p.is_open(0, 1));
+        assert!(p.is_full(0, 1));
+
+        p.open(1, 1);
+        assert!(p.is_full(1, 1));
+        assert!(!p.percolates());
+
+        p.open(2, 0);
+        assert!(!p.is_full(2, 0));
+
+        p.open(2, 1);
+        assert!(p.percolates());
+        assert!(p.is_full(2, 1));
+    }
+
+    #[test]
+    fn no_backwash() {
+        let mut p = Percolation::new(3);
+        (0..3).for_each(|row| p.open(row, 0));
+        assert!(p.percolates());
+
+        // connected to the bottom, but not to the top
+        p.open(2, 2);
+        assert!(!p.is_full(2, 2));
+
+        p.open(1, 2);
+        assert!(!p.is_full(1, 2));
+
+        p.open(0, 2);
+        assert!(p.is_full(2, 2));
+    }
+
+    #[test]
+    fn single_site() {
+        let mut p = Percolation::new(1);
+        assert!(!p.percolates());
+        p.open(0, 0);
+        assert!(p.percolates());
+        assert!(p.is_full(0, 0));
+    }
+
+    #[test]
+    #[should_panic]
+    fn out_of_range() {
+        Percolation::new(2).open(2, 0);
+    }
+
+    #[test]
+    fn stats() {
+        let stats = PercolationStats::new(20, 200);
+        let (low, high) = stats.confidence_interval();
+
+        println!(
+            "mean = {}, stddev = {}, 95% confidence interval = [{}, {}]",
+            stats.mean(),
+            stats.stddev(),
+            low,
+            high
+        );
+
+        assert_eq!(stats.thresholds().len(), 200);
+        assert!(low < stats.mean() && stats.mean() < high);
+        assert!((0.55..0.64).contains(&stats.mean()));
+        assert!(stats.stddev() > 0.0);
+    }
+}
@@ -0,0 +1,411 @@
+use std::{collections::HashMap, error::Error, fmt::Display};
+
+use super::{root_of, UnionFind};
+
+/// Union by size without path compression, so every union touches a single
+/// parent pointer and can be undone. Successful unions are pushed onto a
+/// history stack; `snapshot` marks a point in it and `rollback` undoes
+/// everything after that point.
+#[derive(Debug)]
+pub struct RollbackUnionFind {
+    ids: Vec<usize>,
+    size: Vec<usize>,
+    count: usize,
+    /// roots that were linked under another root, most recent last
+    history: Vec<usize>,
+}
+
+impl RollbackUnionFind {
+    pub fn snapshot(&self) -> usize {
+        self.history.len()
+    }
+
+    /// Undo every union made after `snapshot` returned `to`.
+    pub fn rollback(&mut self, to: usize) {
+        assert!(to <= self.history.len(), "snapshot {} is in the future", to);
+
+        while self.history.len() > to {
+            let child = self.history.pop().unwrap();
+            let parent = self.ids[child];
+
+            self.size[parent] -= self.size[child];
+            self.ids[child] = child;
+            self.count += 1;
+        }
+    }
+}
+
+impl UnionFind for RollbackUnionFind {
+    fn new(count: usize) -> Self {
+        RollbackUnionFind {
+            ids: (0..count).collect(),
+            size: vec![1; count],
+            count,
+            history: Vec::new(),
+        }
+    }
+
+    fn union(&mut self, p: usize, q: usize) -> bool {
+        let p_id = self.find_immutable(p);
+        let q_id = self.find_immutable(q);
+
+        if p_id == q_id {
+            return false;
+        }
+
+        let (child, parent) = if self.size[p_id] < self.size[q_id] {
+            (p_id, q_id)
+        } else {
+            (q_id, p_id)
+        };
+
+        self.ids[child] = parent;
+        self.size[parent] += self.size[child];
+        self.count -= 1;
+        self.history.push(child);
+
+        true
+    }
+
+    fn find(&mut self, p: usize) -> usize {
+        self.find_immutable(p)
+    }
+
+    fn find_immutable(&self, p: usize) -> usize {
+        root_of(&self.ids, p)
+    }
+
+    fn count(&self) -> usize {
+        self.count
+    }
+
+    fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    fn component_size(&self, p: usize) -> usize {
+        self.size[self.find_immutable(p)]
+    }
+
+    /// New elements are not part of the history and survive a rollback.
+    fn add_element(&mut self) -> usize {
+        let p = self.ids.len();
+
+        self.ids.push(p);
+        self.size.push(1);
+        self.count += 1;
+
+        p
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operation {
+    /// add an edge, the same edge may be added more than once
+    Add(usize, usize),
+    /// remove one copy of an edge that is present
+    Remove(usize, usize),
+    Connected(usize, usize),
+    /// number of components
+    Count,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Answer {
+    Connected(bool),
+    Count(usize),
+}
+
+/// Why a timeline can't be answered, with the index of the operation at fault.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimelineError {
+    VertexOutOfRange {
+        time: usize,
+        vertex: usize,
+        count: usize,
+    },
+    /// removal of an edge with no copy present
+    AbsentEdge { time: usize, p: usize, q: usize },
+}
+
+impl Display for TimelineError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TimelineError::VertexOutOfRange {
+                time,
+                vertex,
+                count,
+            } => write!(
+                f,
+                "operation {}: {} is out of range for {} vertices",
+                time, vertex, count
+            ),
+            TimelineError::AbsentEdge { time, p, q } => {
+                write!(f, "operation {}: edge {}-{} is absent", time, p, q)
+            }
+        }
+    }
+}
+
+impl Error for TimelineError {}
+
+/// Answer the queries of a timeline of edge additions and removals over
+/// `count` vertices, in order. The whole timeline is checked before any
+/// query is answered.
+///
+/// Every edge is alive over an interval of the timeline. Each interval is
+/// stored in the O(log m) nodes of a segment tree over the timeline that
+/// cover it, then a depth first walk of the tree unions the edges of a node
+/// on the way down and rolls them back on the way up, so at each leaf the
+/// union-find holds exactly the edges alive at that time. O(m log m log n).
+pub fn dynamic_connectivity(
+    count: usize,
+    operations: &[Operation],
+) -> Result<Vec<Answer>, TimelineError> {
+    let len = operations.len();
+    if len == 0 {
+        return Ok(Vec::new());
+    }
+
+    // alive from the step after the add up to and excluding the remove
+    let mut intervals: Vec<(usize, usize, (usize, usize))> = Vec::new();
+    let mut added_at: HashMap<(usize, usize), Vec<usize>> = HashMap::new();
+
+    let key = |p: usize, q: usize| (p.min(q), p.max(q));
+
+    for (time, operation) in operations.iter().enumerate() {
+        let (p, q) = match *operation {
+            Operation::Add(p, q) | Operation::Remove(p, q) | Operation::Connected(p, q) => (p, q),
+            Operation::Count => continue,
+        };
+
+        if let Some(vertex) = [p, q].into_iter().find(|&vertex| vertex >= count) {
+            return Err(TimelineError::VertexOutOfRange {
+                time,
+                vertex,
+                count,
+            });
+        }
+
+        match operation {
+            Operation::Add(..) => added_at.entry(key(p, q)).or_default().push(time + 1),
+            Operation::Remove(..) => {
+                let start = added_at
+                    .get_mut(&key(p, q))
+                    .and_then(|times| times.pop())
+                    .ok_or(TimelineError::AbsentEdge { time, p, q })?;
+
+                intervals.push((start, time, key(p, q)));
+            }
+            _ => {}
+        }
+    }
+
+    added_at.into_iter().for_each(|(edge, times)| {
+        times
+            .into_iter()
+            .for_each(|start| intervals.push((start, len, edge)));
+    });
+
+    let mut tree: Vec<Vec<(usize, usize)>> = vec![Vec::new(); 4 * len];
+    intervals.into_iter().for_each(|(start, end, edge)| {
+        if start < end {
+            insert(&mut tree, 1, 0, len, start, end, edge);
+        }
+    });
+
+    let mut uf = RollbackUnionFind::new(count);
+    let mut answers = Vec::new();
+    walk(&tree, 1, 0, len, operations, &mut uf, &mut answers);
+
+    Ok(answers)
+}
+
+/// Add `edge` to the nodes covering [start, end) below `node`, which covers
+/// [lo, hi).
+fn insert(
+    tree: &mut [Vec<(usize, usize)>],
+    node: usize,
+    lo: usize,
+    hi: usize,
+    start: usize,
+    end: usize,
+    edge: (usize, usize),
+) {
+    if end <= lo || hi <= start {
+        return;
+    }
+
+    if start <= lo && hi <= end {
+        tree[node].push(edge);
+        return;
+    }
+
+    let mid = (lo + hi) / 2;
+    insert(tree, 2 * node, lo, mid, start, end, edge);
+    insert(tree, 2 * node + 1, mid, hi, start, end, edge);
+}
+
+fn walk(
+    tree: &[Vec<(usize, usize)>],
+    node: usize,
+    lo: usize,
+    hi: usize,
+    operations: &[Operation],
+    uf: &mut RollbackUnionFind,
+    answers: &mut Vec<Answer>,
+) {
+    let snapshot = uf.snapshot();
+
+    tree[node].iter().for_each(|&(p, q)| {
+        uf.union(p, q);
+    });
+
+    if hi - lo == 1 {
+        match operations[lo] {
+            Operation::Connected(p, q) => answers.push(Answer::Connected(uf.connected(p, q))),
+            Operation::Count => answers.push(Answer::Count(uf.count())),
+            _ => {}
+        }
+    } else {
+        let mid = (lo + hi) / 2;
+        walk(tree, 2 * node, lo, mid, operations, uf, answers);
+        walk(tree, 2 * node + 1, mid, hi, operations, uf, answers);
+    }
+
+    uf.rollback(snapshot);
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{thread_rng, Rng};
+
+    use crate::union_find::WeightedQuickUnion;
+
+    use super::*;
+
+    #[test]
+    fn rollback() {
+        let mut uf = RollbackUnionFind::new(6);
+        uf.union(0, 1);
+        let snapshot = uf.snapshot();
+
+        assert!(uf.union(1, 2));
+        assert!(!uf.union(0, 2));
+        assert!(uf.union(3, 4));
+        assert_eq!(uf.count(), 3);
+        assert_eq!(uf.component_size(2), 3);
+
+        uf.rollback(snapshot);
+        assert_eq!(uf.count(), 5);
+        assert!(uf.connected(0, 1));
+        assert!(!uf.connected(1, 2));
+        assert!(!uf.connected(3, 4));
+        assert_eq!(uf.component_size(0), 2);
+
+        uf.rollback(0);
+        assert_eq!(uf.count(), 6);
+        assert_eq!(uf.components().len(), 6);
+    }
+
+    #[test]
+    fn timeline() {
+        use Operation::*;
+
+        let operations = [
+            Add(0, 1),
+            Add(1, 2),
+            Connected(0, 2),
+            Count,
+            Add(2, 1),
+            Remove(1, 2),
+            Connected(0, 2),
+            Remove(1, 2),
+            Connected(0, 2),
+            Count,
+            Add(3, 3),
+            Count,
+        ];
+
+        assert_eq!(
+            dynamic_connectivity(4, &operations).unwrap(),
+            [
+                Answer::Connected(true),
+                Answer::Count(2),
+                Answer::Connected(true),
+                Answer::Connected(false),
+                Answer::Count(3),
+                Answer::Count(3),
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid_timelines() {
+        use Operation::*;
+
+        assert_eq!(
+            dynamic_connectivity(2, &[Add(0, 1), Remove(1, 0), Remove(0, 1)]),
+            Err(TimelineError::AbsentEdge {
+                time: 2,
+                p: 0,
+                q: 1
+            })
+        );
+        assert_eq!(
+            dynamic_connectivity(2, &[Count, Connected(0, 2)]),
+            Err(TimelineError::VertexOutOfRange {
+                time: 1,
+                vertex: 2,
+                count: 2
+            })
+        );
+        assert_eq!(
+            dynamic_connectivity(2, &[Remove(0, 1)])
+                .unwrap_err()
+                .to_string(),
+            "operation 0: edge 0-1 is absent"
+        );
+    }
+
+    #[test]
+    fn brute_force() {
+        let mut rng = thread_rng();
+        let count = 12;
+
+        (0..20).for_each(|_| {
+            let mut edges: Vec<(usize, usize)> = Vec::new();
+            let mut operations = Vec::new();
+            let mut expected = Vec::new();
+
+            (0..300).for_each(|_| match rng.gen_range(0..4) {
+                0 | 1 => {
+                    let edge = (rng.gen_range(0..count), rng.gen_range(0..count));
+                    edges.push(edge);
+                    operations.push(Operation::Add(edge.0, edge.1));
+                }
+                2 if !edges.is_empty() => {
+                    let (p, q) = edges.swap_remove(rng.gen_range(0..edges.len()));
+                    operations.push(Operation::Remove(q, p));
+                }
+                _ => {
+                    let mut uf = WeightedQuickUnion::new(count);
+                    edges.iter().for_each(|&(p, q)| {
+                        uf.union(p, q);
+                    });
+
+                    if rng.gen_bool(0.5) {
+                        operations.push(Operation::Count);
+                        expected.push(Answer::Count(uf.count()));
+                    } else {
+                        let (p, q) = (rng.gen_range(0..count), rng.gen_range(0..count));
+                        operations.push(Operation::Connected(p, q));
+                        expected.push(Answer::Connected(uf.connected(p, q)));
+                    }
+                }
+            });
+
+            assert_eq!(dynamic_connectivity(count, &operations).unwrap(), expected);
+        });
+    }
+}